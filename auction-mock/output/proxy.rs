@@ -62,17 +62,18 @@ where
     To: TxTo<Env>,
     Gas: TxGas<Env>,
 {
+    /// Without arguments, the payment is added to the top-up of the already staked nodes. 
     pub fn stake<
-        Arg0: ProxyArg<usize>,
+        Arg0: ProxyArg<OptionalValue<usize>>,
         Arg1: ProxyArg<MultiValueEncoded<Env::Api, MultiValue2<ManagedBuffer<Env::Api>, ManagedBuffer<Env::Api>>>>,
     >(
         self,
-        num_nodes: Arg0,
+        opt_num_nodes: Arg0,
         bls_keys_signatures: Arg1,
     ) -> TxTypedCall<Env, From, To, (), Gas, MultiValueEncoded<Env::Api, ManagedBuffer<Env::Api>>> {
         self.wrapped_tx
             .raw_call("stake")
-            .argument(&num_nodes)
+            .argument(&opt_num_nodes)
             .argument(&bls_keys_signatures)
             .original_result()
     }
@@ -129,16 +130,17 @@ where
             .original_result()
     }
 
+    /// Lowers the total stake, and with it the top-up of the staked nodes. 
     pub fn unstake_tokens<
         Arg0: ProxyArg<BigUint<Env::Api>>,
     >(
         self,
-        _amount: Arg0,
+        amount: Arg0,
     ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
         self.wrapped_tx
             .payment(NotPayable)
             .raw_call("unStakeTokens")
-            .argument(&_amount)
+            .argument(&amount)
             .original_result()
     }
 
@@ -155,6 +157,7 @@ where
             .original_result()
     }
 
+    /// Sends back the stake of the nodes that could not be staked. 
     pub fn claim(
         self,
     ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
@@ -176,6 +179,67 @@ where
             .original_result()
     }
 
+    /// Overrides the status of a key, to simulate the delegation contract drifting from the auction. 
+    /// An empty status removes the key from the `getBlsKeysStatus` result. 
+    pub fn set_bls_key_status_endpoint<
+        Arg0: ProxyArg<ManagedBuffer<Env::Api>>,
+        Arg1: ProxyArg<ManagedBuffer<Env::Api>>,
+    >(
+        self,
+        bls_key: Arg0,
+        status: Arg1,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("setBlsKeyStatus")
+            .argument(&bls_key)
+            .argument(&status)
+            .original_result()
+    }
+
+    /// Statuses of all the keys staked with the mock, regardless of owner. 
+    pub fn get_bls_keys_status<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        _owner: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, MultiValue2<ManagedBuffer<Env::Api>, ManagedBuffer<Env::Api>>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getBlsKeysStatus")
+            .argument(&_owner)
+            .original_result()
+    }
+
+    /// Top-up, total stake, number of staked keys, followed by the staked keys. 
+    pub fn get_total_staked_top_up_staked_bls_keys<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        _owner: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, ManagedBuffer<Env::Api>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getTotalStakedTopUpStakedBlsKeys")
+            .argument(&_owner)
+            .original_result()
+    }
+
+    /// Makes the node endpoints return results that the delegation callbacks cannot decode, 
+    /// so the callback fails, as if it was lost. 
+    pub fn set_malformed_results<
+        Arg0: ProxyArg<bool>,
+    >(
+        self,
+        malformed: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("setMalformedResults")
+            .argument(&malformed)
+            .original_result()
+    }
+
     pub fn set_bls_deliberate_error<
         Arg0: ProxyArg<Box<[u8]>>,
         Arg1: ProxyArg<u8>,
@@ -204,4 +268,189 @@ where
             .argument(&bls_key)
             .original_result()
     }
+
+    pub fn set_unbond_period<
+        Arg0: ProxyArg<u64>,
+    >(
+        self,
+        epochs: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("setUnbondPeriod")
+            .argument(&epochs)
+            .original_result()
+    }
+
+    pub fn set_unjail_fine_per_node<
+        Arg0: ProxyArg<BigUint<Env::Api>>,
+    >(
+        self,
+        fine: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("setUnjailFinePerNode")
+            .argument(&fine)
+            .original_result()
+    }
+
+    /// Withholds stake from the nodes unbonded next, as if they had been slashed. 
+    pub fn set_slashed_stake<
+        Arg0: ProxyArg<BigUint<Env::Api>>,
+    >(
+        self,
+        amount: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("setSlashedStake")
+            .argument(&amount)
+            .original_result()
+    }
+
+    pub fn set_reward_per_node_per_epoch<
+        Arg0: ProxyArg<BigUint<Env::Api>>,
+    >(
+        self,
+        reward: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("setRewardPerNodePerEpoch")
+            .argument(&reward)
+            .original_result()
+    }
+
+    /// Jails staked nodes, as the protocol would for nodes that misbehave. 
+    pub fn jail_nodes<
+        Arg0: ProxyArg<MultiValueEncoded<Env::Api, ManagedBuffer<Env::Api>>>,
+    >(
+        self,
+        bls_keys: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("jailNodes")
+            .argument(&bls_keys)
+            .original_result()
+    }
+
+    pub fn get_accrued_rewards(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, BigUint<Env::Api>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getAccruedRewards")
+            .original_result()
+    }
+
+    /// Returns the accrued rewards and starts accruing from zero. 
+    /// The result is meant to be credited to the delegation contract with a `validatorReward` step. 
+    pub fn collect_rewards(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, BigUint<Env::Api>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("collectRewards")
+            .original_result()
+    }
+
+    pub fn get_num_calls(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, usize> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getNumCalls")
+            .original_result()
+    }
+
+    /// Calls in the order they were received, starting at 0. 
+    pub fn get_call_history<
+        Arg0: ProxyArg<usize>,
+        Arg1: ProxyArg<usize>,
+    >(
+        self,
+        from: Arg0,
+        count: Arg1,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, AuctionCall<Env::Api>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getCallHistory")
+            .argument(&from)
+            .argument(&count)
+            .original_result()
+    }
+
+    pub fn get_last_call(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, OptionalValue<AuctionCall<Env::Api>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getLastCall")
+            .original_result()
+    }
+
+    /// All the calls to one endpoint, in the order they were received. 
+    pub fn get_calls_to_endpoint<
+        Arg0: ProxyArg<ManagedBuffer<Env::Api>>,
+    >(
+        self,
+        endpoint: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, AuctionCall<Env::Api>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getCallsToEndpoint")
+            .argument(&endpoint)
+            .original_result()
+    }
+
+    pub fn set_endpoint_failure_mode<
+        Arg0: ProxyArg<ManagedBuffer<Env::Api>>,
+        Arg1: ProxyArg<EndpointFailureMode>,
+    >(
+        self,
+        endpoint: Arg0,
+        mode: Arg1,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("setEndpointFailureMode")
+            .argument(&endpoint)
+            .argument(&mode)
+            .original_result()
+    }
+
+    pub fn get_endpoint_failure_mode<
+        Arg0: ProxyArg<ManagedBuffer<Env::Api>>,
+    >(
+        self,
+        endpoint: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, EndpointFailureMode> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getEndpointFailureMode")
+            .argument(&endpoint)
+            .original_result()
+    }
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, Clone, PartialEq, Debug)]
+pub struct AuctionCall<Api>
+where
+    Api: ManagedTypeApi,
+{
+    pub endpoint: ManagedBuffer<Api>,
+    pub caller: ManagedAddress<Api>,
+    pub payment: BigUint<Api>,
+    pub bls_keys: ManagedVec<Api, ManagedBuffer<Api>>,
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EndpointFailureMode {
+    None,
+    Error,
+    RejectAllKeys,
 }
@@ -0,0 +1,82 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+/// One call received by the mock, as sent by the delegation contract.
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, Clone, PartialEq, Debug)]
+pub struct AuctionCall<M: ManagedTypeApi> {
+    pub endpoint: ManagedBuffer<M>,
+    pub caller: ManagedAddress<M>,
+    pub payment: BigUint<M>,
+
+    /// Only the keys, signatures sent to `stake` are not logged.
+    pub bls_keys: ManagedVec<M, ManagedBuffer<M>>,
+}
+
+/// Append-only log of the calls to the node and funds endpoints of the mock,
+/// so tests can assert exactly what the delegation contract sent.
+///
+/// Calls that fail are reverted, so they do not appear in the log.
+#[multiversx_sc::derive::module]
+pub trait CallHistoryModule {
+    #[storage_mapper("call_history")]
+    fn call_history(&self) -> VecMapper<AuctionCall<Self::Api>>;
+
+    fn record_call(
+        &self,
+        endpoint: &[u8],
+        bls_keys: ManagedVec<ManagedBuffer>,
+        payment: &BigUint,
+    ) {
+        self.call_history().push(&AuctionCall {
+            endpoint: ManagedBuffer::from(endpoint),
+            caller: self.blockchain().get_caller(),
+            payment: payment.clone(),
+            bls_keys,
+        });
+    }
+
+    #[view(getNumCalls)]
+    fn get_num_calls(&self) -> usize {
+        self.call_history().len()
+    }
+
+    /// Calls in the order they were received, starting at 0.
+    #[view(getCallHistory)]
+    fn get_call_history(
+        &self,
+        from: usize,
+        count: usize,
+    ) -> MultiValueEncoded<AuctionCall<Self::Api>> {
+        let mut result = MultiValueEncoded::new();
+        for call in self.call_history().iter().skip(from).take(count) {
+            result.push(call);
+        }
+        result
+    }
+
+    #[view(getLastCall)]
+    fn get_last_call(&self) -> OptionalValue<AuctionCall<Self::Api>> {
+        let call_history = self.call_history();
+        if call_history.is_empty() {
+            OptionalValue::None
+        } else {
+            OptionalValue::Some(call_history.get(call_history.len()))
+        }
+    }
+
+    /// All the calls to one endpoint, in the order they were received.
+    #[view(getCallsToEndpoint)]
+    fn get_calls_to_endpoint(
+        &self,
+        endpoint: ManagedBuffer,
+    ) -> MultiValueEncoded<AuctionCall<Self::Api>> {
+        let mut result = MultiValueEncoded::new();
+        for call in self.call_history().iter() {
+            if call.endpoint == endpoint {
+                result.push(call);
+            }
+        }
+        result
+    }
+}
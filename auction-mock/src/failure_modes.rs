@@ -0,0 +1,57 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+/// Per-key error code returned for all keys of an endpoint configured with `RejectAllKeys`.
+pub const REJECTED_KEY_ERR_CODE: u8 = 2;
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EndpointFailureMode {
+    None,
+
+    /// The call fails, as with the global `staking_failure`.
+    Error,
+
+    /// The call succeeds, but every key gets an error code.
+    /// Endpoints that do not return per-key results fail instead.
+    RejectAllKeys,
+}
+
+/// Failures configured per endpoint, on top of the global `staking_failure`
+/// and the per-key `bls_deliberate_error`.
+#[multiversx_sc::derive::module]
+pub trait FailureModesModule {
+    #[endpoint(setEndpointFailureMode)]
+    fn set_endpoint_failure_mode(&self, endpoint: ManagedBuffer, mode: EndpointFailureMode) {
+        if mode == EndpointFailureMode::None {
+            self.endpoint_failure_mode(&endpoint).clear();
+        } else {
+            self.endpoint_failure_mode(&endpoint).set(mode);
+        }
+    }
+
+    #[view(getEndpointFailureMode)]
+    fn get_endpoint_failure_mode(&self, endpoint: ManagedBuffer) -> EndpointFailureMode {
+        let mapper = self.endpoint_failure_mode(&endpoint);
+        if mapper.is_empty() {
+            EndpointFailureMode::None
+        } else {
+            mapper.get()
+        }
+    }
+
+    /// Fails if so configured, otherwise returns whether all keys should be rejected.
+    fn check_endpoint_failure_mode(&self, endpoint: &[u8], has_key_results: bool) -> bool {
+        match self.get_endpoint_failure_mode(ManagedBuffer::from(endpoint)) {
+            EndpointFailureMode::None => false,
+            EndpointFailureMode::RejectAllKeys if has_key_results => true,
+            _ => sc_panic!("auction smart contract deliberate error"),
+        }
+    }
+
+    #[storage_mapper("endpoint_failure_mode")]
+    fn endpoint_failure_mode(
+        &self,
+        endpoint: &ManagedBuffer,
+    ) -> SingleValueMapper<EndpointFailureMode>;
+}
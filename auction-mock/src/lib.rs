@@ -1,25 +1,52 @@
 #![no_std]
 #![allow(clippy::type_complexity)]
 
+mod call_history;
+mod failure_modes;
+mod lifecycle;
 mod storage;
 
+use failure_modes::REJECTED_KEY_ERR_CODE;
+use lifecycle::{STATUS_JAILED, STATUS_STAKED, STATUS_UNSTAKED, UNBOND_NOT_ALLOWED_ERR_CODE};
 use node_storage::types::bls_key::BLSKey;
 
 multiversx_sc::imports!();
 
 #[multiversx_sc::derive::contract]
-pub trait AuctionMock: storage::AuctionMockStorage {
+pub trait AuctionMock:
+    storage::AuctionMockStorage
+    + lifecycle::LifecycleModule
+    + call_history::CallHistoryModule
+    + failure_modes::FailureModesModule
+{
     #[init]
     fn init(&self) {}
 
+    /// Without arguments, the payment is added to the top-up of the already staked nodes.
     #[payable("EGLD")]
     #[endpoint]
+    #[allow_multiple_var_args]
     fn stake(
         &self,
-        num_nodes: usize,
+        opt_num_nodes: OptionalValue<usize>,
         bls_keys_signatures: MultiValueEncoded<MultiValue2<ManagedBuffer, ManagedBuffer>>,
         #[payment] payment: BigUint,
     ) -> MultiValueEncoded<ManagedBuffer> {
+        let num_nodes = match opt_num_nodes {
+            OptionalValue::Some(num_nodes) => num_nodes,
+            OptionalValue::None => {
+                self.check_endpoint_failure_mode(b"stake", false);
+                require!(
+                    !self.is_staking_failure(),
+                    "auction smart contract deliberate error"
+                );
+                require!(payment > 0, "no top-up provided");
+                self.total_staked().update(|total| *total += &payment);
+                self.record_call(b"stake", ManagedVec::new(), &payment);
+                return MultiValueEncoded::new();
+            }
+        };
+
         require!(
             num_nodes * 2 == bls_keys_signatures.raw_len(),
             "incorrect number of arguments"
@@ -29,6 +56,7 @@ pub trait AuctionMock: storage::AuctionMockStorage {
             !self.is_staking_failure(),
             "auction smart contract deliberate error"
         );
+        let reject_all_keys = self.check_endpoint_failure_mode(b"stake", true);
 
         let mut new_num_nodes = self.get_num_nodes();
         let expected_payment = BigUint::from(num_nodes as u64) * self.get_stake_per_node();
@@ -37,22 +65,34 @@ pub trait AuctionMock: storage::AuctionMockStorage {
             "incorrect payment to auction mock"
         );
 
+        self.accrue_rewards();
+        let mut unused_stake = BigUint::zero();
+        let mut bls_keys = ManagedVec::new();
         let mut result_err_data: MultiValueEncoded<ManagedBuffer> = MultiValueEncoded::new();
         for key_sig_pair in bls_keys_signatures.into_iter() {
             new_num_nodes += 1;
             let (bls_key, bls_sig) = key_sig_pair.into_tuple();
             self.set_stake_bls_key(new_num_nodes, bls_key.to_boxed_bytes().as_slice());
             self.set_stake_bls_signature(new_num_nodes, bls_sig.to_boxed_bytes().as_slice());
+            bls_keys.push(bls_key.clone());
 
-            let err_code = self.get_bls_deliberate_error(bls_key.to_boxed_bytes().as_slice());
+            let err_code = self.key_err_code(&bls_key, reject_all_keys);
             if err_code > 0 {
                 result_err_data.push(bls_key);
                 result_err_data.push(ManagedBuffer::from(&[err_code][..]));
+                unused_stake += self.get_stake_per_node();
+            } else {
+                self.set_bls_key_status(&bls_key, STATUS_STAKED);
             }
         }
 
         self.set_num_nodes(new_num_nodes);
+        self.total_staked()
+            .update(|total| *total += &payment - &unused_stake);
+        self.unused_funds().update(|unused| *unused += unused_stake);
+        self.record_call(b"stake", bls_keys, &payment);
 
+        self.push_malformed_result_if_configured(&mut result_err_data);
         result_err_data
     }
 
@@ -60,88 +100,264 @@ pub trait AuctionMock: storage::AuctionMockStorage {
     fn unstake_endpoint(
         &self,
         bls_keys: MultiValueEncoded<ManagedBuffer>,
+    ) -> MultiValueEncoded<ManagedBuffer> {
+        self.unstake_keys(b"unStake", bls_keys)
+    }
+
+    #[endpoint(unStakeNodes)]
+    fn unstake_nodes_endpoint(
+        &self,
+        bls_keys: MultiValueEncoded<ManagedBuffer>,
+    ) -> MultiValueEncoded<ManagedBuffer> {
+        self.unstake_keys(b"unStakeNodes", bls_keys)
+    }
+
+    fn unstake_keys(
+        &self,
+        endpoint: &[u8],
+        bls_keys: MultiValueEncoded<ManagedBuffer>,
     ) -> MultiValueEncoded<ManagedBuffer> {
         require!(
             !self.is_staking_failure(),
             "auction smart contract deliberate error"
         );
+        let reject_all_keys = self.check_endpoint_failure_mode(endpoint, true);
+        let bls_keys = bls_keys.to_vec();
 
+        self.accrue_rewards();
+        let current_epoch = self.blockchain().get_block_epoch();
         let mut result_err_data: MultiValueEncoded<ManagedBuffer> = MultiValueEncoded::new();
-        for (n, bls_key) in bls_keys.into_iter().enumerate() {
+        for (n, bls_key) in bls_keys.iter().enumerate() {
             self.set_unstake_bls_key(n, bls_key.to_boxed_bytes().as_slice());
 
-            let err_code = self.get_bls_deliberate_error(bls_key.to_boxed_bytes().as_slice());
+            let err_code = self.key_err_code(&bls_key, reject_all_keys);
             if err_code > 0 {
                 result_err_data.push(bls_key.clone());
                 result_err_data.push(ManagedBuffer::from(&[err_code][..]));
+            } else {
+                self.set_bls_key_status(&bls_key, STATUS_UNSTAKED);
+                self.unstake_epoch(&bls_key).set(current_epoch);
             }
         }
 
+        self.record_call(endpoint, bls_keys, &BigUint::zero());
+        self.push_malformed_result_if_configured(&mut result_err_data);
         result_err_data
     }
 
-    #[endpoint(unStakeNodes)]
-    fn unstake_nodes_endpoint(
+    #[endpoint(unBond)]
+    fn unbond_endpoint(
         &self,
         bls_keys: MultiValueEncoded<ManagedBuffer>,
     ) -> MultiValueEncoded<ManagedBuffer> {
-        self.unstake_endpoint(bls_keys)
+        self.unbond_keys(b"unBond", bls_keys)
     }
 
-    #[endpoint(unBond)]
-    fn unbond_endpoint(
+    #[endpoint(unBondNodes)]
+    fn unbond_nodes_endpoint(
         &self,
         bls_keys: MultiValueEncoded<ManagedBuffer>,
+    ) -> MultiValueEncoded<ManagedBuffer> {
+        self.unbond_keys(b"unBondNodes", bls_keys)
+    }
+
+    fn unbond_keys(
+        &self,
+        endpoint: &[u8],
+        bls_keys: MultiValueEncoded<ManagedBuffer>,
     ) -> MultiValueEncoded<ManagedBuffer> {
         require!(
             !self.is_staking_failure(),
             "auction smart contract deliberate error"
         );
+        let reject_all_keys = self.check_endpoint_failure_mode(endpoint, true);
+        let bls_keys = bls_keys.to_vec();
 
         let mut result_err_data: MultiValueEncoded<ManagedBuffer> = MultiValueEncoded::new();
-        let bls_keys_len = bls_keys.len();
-        for (n, bls_key) in bls_keys.into_iter().enumerate() {
+        let mut num_unbonded_nodes = 0u64;
+        for (n, bls_key) in bls_keys.iter().enumerate() {
             self.set_unbond_bls_key(n, bls_key.to_boxed_bytes().as_slice());
 
-            let err_code = self.get_bls_deliberate_error(bls_key.to_boxed_bytes().as_slice());
+            let mut err_code = self.key_err_code(&bls_key, reject_all_keys);
+            if err_code == 0 && !self.can_unbond(&bls_key) {
+                err_code = UNBOND_NOT_ALLOWED_ERR_CODE;
+            }
+
             if err_code > 0 {
                 result_err_data.push(bls_key.clone());
                 result_err_data.push(ManagedBuffer::from(&[err_code][..]));
+            } else {
+                self.bls_key_status(&bls_key).clear();
+                self.unstake_epoch(&bls_key).clear();
+                num_unbonded_nodes += 1;
             }
         }
 
-        let unbond_stake = self.get_stake_per_node() * BigUint::from(bls_keys_len);
-        self.tx().to(ToCaller).egld(unbond_stake).transfer();
+        // only the stake of the nodes actually unbonded is returned, minus what was slashed
+        let unbond_stake = self.get_stake_per_node() * num_unbonded_nodes;
+        self.total_staked().update(|total| {
+            *total = if *total > unbond_stake {
+                &*total - &unbond_stake
+            } else {
+                BigUint::zero()
+            }
+        });
+        let slashed_stake = core::cmp::min(self.slashed_stake().get(), unbond_stake.clone());
+        self.slashed_stake()
+            .update(|remaining| *remaining -= &slashed_stake);
+        self.tx()
+            .to(ToCaller)
+            .egld(unbond_stake - slashed_stake)
+            .transfer();
 
+        self.record_call(endpoint, bls_keys, &BigUint::zero());
+        self.push_malformed_result_if_configured(&mut result_err_data);
         result_err_data
     }
 
-    #[endpoint(unBondNodes)]
-    fn unbond_nodes_endpoint(
+    /// The deliberate error of the key, if any, otherwise the rejection of the whole call.
+    fn key_err_code(&self, bls_key: &ManagedBuffer, reject_all_keys: bool) -> u8 {
+        let err_code = self.get_bls_deliberate_error(bls_key.to_boxed_bytes().as_slice());
+        if err_code == 0 && reject_all_keys {
+            REJECTED_KEY_ERR_CODE
+        } else {
+            err_code
+        }
+    }
+
+    fn push_malformed_result_if_configured(
         &self,
-        bls_keys: MultiValueEncoded<ManagedBuffer>,
-    ) -> MultiValueEncoded<ManagedBuffer> {
-        self.unbond_endpoint(bls_keys)
+        result_err_data: &mut MultiValueEncoded<ManagedBuffer>,
+    ) {
+        if self.is_malformed_results() {
+            // a lone, short BLS key, without status
+            result_err_data.push(ManagedBuffer::from(b"malformed"));
+        }
     }
 
+    /// Lowers the total stake, and with it the top-up of the staked nodes.
     #[endpoint(unStakeTokens)]
-    fn unstake_tokens(&self, _amount: BigUint) {}
+    fn unstake_tokens(&self, amount: BigUint) {
+        self.check_endpoint_failure_mode(b"unStakeTokens", false);
+        self.record_call(b"unStakeTokens", ManagedVec::new(), &amount);
+        self.total_staked().update(|total| {
+            if *total > amount {
+                *total -= &amount;
+            } else {
+                *total = BigUint::zero();
+            }
+        });
+    }
 
     #[endpoint(unBondTokens)]
     fn unbond_tokens(&self, amount: BigUint) {
+        self.check_endpoint_failure_mode(b"unBondTokens", false);
+        self.record_call(b"unBondTokens", ManagedVec::new(), &amount);
         self.tx().to(ToCaller).egld(amount).transfer();
     }
 
+    /// Sends back the stake of the nodes that could not be staked.
     #[endpoint]
-    fn claim(&self) {}
+    fn claim(&self) {
+        self.check_endpoint_failure_mode(b"claim", false);
+        self.record_call(b"claim", ManagedVec::new(), &BigUint::zero());
+        let unused_funds = self.unused_funds().take();
+        if unused_funds > 0 {
+            self.tx().to(ToCaller).egld(unused_funds).transfer();
+        }
+    }
 
     #[payable("EGLD")]
     #[endpoint(unJail)]
     fn unjail_endpoint(
         &self,
         bls_keys: MultiValueManagedVec<BLSKey<Self::Api>>,
-        #[payment] _fine_payment: BigUint,
+        #[payment] fine_payment: BigUint,
     ) {
-        self.set_unjailed(&bls_keys.into_vec());
+        self.check_endpoint_failure_mode(b"unJail", false);
+        let bls_keys = bls_keys.into_vec();
+        let fine_per_node = self.unjail_fine_per_node().get();
+        if fine_per_node > 0 {
+            require!(
+                fine_payment == fine_per_node * bls_keys.len() as u64,
+                "incorrect unjail fine"
+            );
+        }
+
+        self.accrue_rewards();
+        for bls_key in bls_keys.iter() {
+            let bls_key_bytes = bls_key.bytes.as_managed_buffer();
+            if self.bls_key_status(bls_key_bytes).get() == *STATUS_JAILED {
+                self.set_bls_key_status(bls_key_bytes, STATUS_STAKED);
+            }
+        }
+        self.set_unjailed(&bls_keys);
+
+        let mut logged_keys = ManagedVec::new();
+        for bls_key in bls_keys.iter() {
+            logged_keys.push(bls_key.bytes.as_managed_buffer().clone());
+        }
+        self.record_call(b"unJail", logged_keys, &fine_payment);
+    }
+
+    fn set_bls_key_status(&self, bls_key: &ManagedBuffer, status: &[u8]) {
+        self.known_bls_keys().insert(bls_key.clone());
+        self.bls_key_status(bls_key)
+            .set(ManagedBuffer::from(status));
+    }
+
+    /// Overrides the status of a key, to simulate the delegation contract drifting from the auction.
+    /// An empty status removes the key from the `getBlsKeysStatus` result.
+    #[endpoint(setBlsKeyStatus)]
+    fn set_bls_key_status_endpoint(&self, bls_key: ManagedBuffer, status: ManagedBuffer) {
+        self.known_bls_keys().insert(bls_key.clone());
+        self.bls_key_status(&bls_key).set(status);
+    }
+
+    /// Statuses of all the keys staked with the mock, regardless of owner.
+    #[endpoint(getBlsKeysStatus)]
+    fn get_bls_keys_status(
+        &self,
+        _owner: ManagedAddress,
+    ) -> MultiValueEncoded<MultiValue2<ManagedBuffer, ManagedBuffer>> {
+        let mut result = MultiValueEncoded::new();
+        for bls_key in self.known_bls_keys().iter() {
+            let status = self.bls_key_status(&bls_key).get();
+            if !status.is_empty() {
+                result.push(MultiValue2::from((bls_key, status)));
+            }
+        }
+        result
+    }
+
+    /// Top-up, total stake, number of staked keys, followed by the staked keys.
+    #[endpoint(getTotalStakedTopUpStakedBlsKeys)]
+    fn get_total_staked_top_up_staked_bls_keys(
+        &self,
+        _owner: ManagedAddress,
+    ) -> MultiValueEncoded<ManagedBuffer> {
+        let mut staked_keys = ManagedVec::<Self::Api, ManagedBuffer>::new();
+        for bls_key in self.known_bls_keys().iter() {
+            if self.bls_key_status(&bls_key).get() == *STATUS_STAKED {
+                staked_keys.push(bls_key);
+            }
+        }
+
+        let total_staked = self.total_staked().get();
+        let base_stake = self.get_stake_per_node() * BigUint::from(staked_keys.len());
+        let top_up = if total_staked > base_stake {
+            &total_staked - &base_stake
+        } else {
+            BigUint::zero()
+        };
+
+        let mut result = MultiValueEncoded::new();
+        result.push(top_up.to_bytes_be_buffer());
+        result.push(total_staked.to_bytes_be_buffer());
+        result.push(BigUint::from(staked_keys.len()).to_bytes_be_buffer());
+        for bls_key in staked_keys.iter() {
+            result.push(bls_key.clone());
+        }
+        result
     }
 }
@@ -0,0 +1,102 @@
+multiversx_sc::imports!();
+
+pub const STATUS_STAKED: &[u8] = b"staked";
+pub const STATUS_UNSTAKED: &[u8] = b"unStaked";
+pub const STATUS_JAILED: &[u8] = b"jailed";
+
+/// Per-key error code returned by `unBond` for keys that are not unstaked, or still in the unbond period.
+pub const UNBOND_NOT_ALLOWED_ERR_CODE: u8 = 1;
+
+/// Models the passing of time in the staking system SC:
+/// - unstaked nodes can only be unbonded after an unbond period, in epochs,
+/// - staked nodes can be jailed, and unjailed for a fine,
+/// - stake can be slashed, and is then missing when the nodes are unbonded,
+/// - staked nodes that are not jailed accrue rewards every epoch.
+///
+/// Rewards cannot be sent as such by a contract, they are credited by the protocol.
+/// The mock only computes them, tests then credit them with a `validatorReward` step.
+#[multiversx_sc::derive::module]
+pub trait LifecycleModule: crate::storage::AuctionMockStorage {
+    #[endpoint(setUnbondPeriod)]
+    fn set_unbond_period(&self, epochs: u64) {
+        self.unbond_period().set(epochs);
+    }
+
+    #[endpoint(setUnjailFinePerNode)]
+    fn set_unjail_fine_per_node(&self, fine: BigUint) {
+        self.unjail_fine_per_node().set(fine);
+    }
+
+    /// Withholds stake from the nodes unbonded next, as if they had been slashed.
+    #[endpoint(setSlashedStake)]
+    fn set_slashed_stake(&self, amount: BigUint) {
+        self.slashed_stake().set(amount);
+    }
+
+    #[endpoint(setRewardPerNodePerEpoch)]
+    fn set_reward_per_node_per_epoch(&self, reward: BigUint) {
+        self.accrue_rewards();
+        self.reward_per_node_per_epoch().set(reward);
+    }
+
+    /// Jails staked nodes, as the protocol would for nodes that misbehave.
+    #[endpoint(jailNodes)]
+    fn jail_nodes(&self, bls_keys: MultiValueEncoded<ManagedBuffer>) {
+        self.accrue_rewards();
+        for bls_key in bls_keys.into_iter() {
+            require!(
+                self.bls_key_status(&bls_key).get() == *STATUS_STAKED,
+                "only staked nodes can be jailed"
+            );
+            self.bls_key_status(&bls_key)
+                .set(ManagedBuffer::from(STATUS_JAILED));
+        }
+    }
+
+    fn can_unbond(&self, bls_key: &ManagedBuffer) -> bool {
+        if self.bls_key_status(bls_key).get() != *STATUS_UNSTAKED {
+            return false;
+        }
+
+        let current_epoch = self.blockchain().get_block_epoch();
+        current_epoch >= self.unstake_epoch(bls_key).get() + self.unbond_period().get()
+    }
+
+    fn num_rewarded_nodes(&self) -> u64 {
+        let mut num_nodes = 0u64;
+        for bls_key in self.known_bls_keys().iter() {
+            if self.bls_key_status(&bls_key).get() == *STATUS_STAKED {
+                num_nodes += 1;
+            }
+        }
+        num_nodes
+    }
+
+    /// Rewards accrued since the last update, for the nodes currently staked and not jailed.
+    fn rewards_since_last_update(&self) -> BigUint {
+        let current_epoch = self.blockchain().get_block_epoch();
+        let num_epochs = current_epoch.saturating_sub(self.last_reward_epoch().get());
+        self.reward_per_node_per_epoch().get() * num_epochs * self.num_rewarded_nodes()
+    }
+
+    /// Needs to be called before every change in the set of rewarded nodes.
+    fn accrue_rewards(&self) {
+        let new_rewards = self.rewards_since_last_update();
+        self.accrued_rewards().update(|accrued| *accrued += new_rewards);
+        self.last_reward_epoch()
+            .set(self.blockchain().get_block_epoch());
+    }
+
+    #[view(getAccruedRewards)]
+    fn get_accrued_rewards(&self) -> BigUint {
+        self.accrued_rewards().get() + self.rewards_since_last_update()
+    }
+
+    /// Returns the accrued rewards and starts accruing from zero.
+    /// The result is meant to be credited to the delegation contract with a `validatorReward` step.
+    #[endpoint(collectRewards)]
+    fn collect_rewards(&self) -> BigUint {
+        self.accrue_rewards();
+        self.accrued_rewards().take()
+    }
+}
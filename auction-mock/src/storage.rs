@@ -31,6 +31,15 @@ pub trait AuctionMockStorage {
     #[storage_get("staking_failure")]
     fn is_staking_failure(&self) -> bool;
 
+    /// Makes the node endpoints return results that the delegation callbacks cannot decode,
+    /// so the callback fails, as if it was lost.
+    #[endpoint(setMalformedResults)]
+    #[storage_set("malformed_results")]
+    fn set_malformed_results(&self, malformed: bool);
+
+    #[storage_get("malformed_results")]
+    fn is_malformed_results(&self) -> bool;
+
     #[endpoint(setBlsDeliberateError)]
     #[storage_set("bls_deliberate_error")]
     fn set_bls_deliberate_error(&self, bls_key: &[u8], err_code: u8);
@@ -39,6 +48,43 @@ pub trait AuctionMockStorage {
     #[storage_get("bls_deliberate_error")]
     fn get_bls_deliberate_error(&self, bls_key: &[u8]) -> u8;
 
+    /// Status of each key, as reported by `getBlsKeysStatus`. Empty for unknown or unbonded keys.
+    #[storage_mapper("bls_key_status")]
+    fn bls_key_status(&self, bls_key: &ManagedBuffer) -> SingleValueMapper<ManagedBuffer>;
+
+    #[storage_mapper("known_bls_keys")]
+    fn known_bls_keys(&self) -> UnorderedSetMapper<ManagedBuffer>;
+
+    #[storage_mapper("total_staked")]
+    fn total_staked(&self) -> SingleValueMapper<BigUint>;
+
+    /// Unbond period, in epochs.
+    #[storage_mapper("unbond_period")]
+    fn unbond_period(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("unstake_epoch")]
+    fn unstake_epoch(&self, bls_key: &ManagedBuffer) -> SingleValueMapper<u64>;
+
+    /// Stake sent for nodes that could not be staked, available to `claim`.
+    #[storage_mapper("unused_funds")]
+    fn unused_funds(&self) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("unjail_fine_per_node")]
+    fn unjail_fine_per_node(&self) -> SingleValueMapper<BigUint>;
+
+    /// Stake still to be withheld from the nodes unbonded next.
+    #[storage_mapper("slashed_stake")]
+    fn slashed_stake(&self) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("reward_per_node_per_epoch")]
+    fn reward_per_node_per_epoch(&self) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("last_reward_epoch")]
+    fn last_reward_epoch(&self) -> SingleValueMapper<u64>;
+
+    #[storage_mapper("accrued_rewards")]
+    fn accrued_rewards(&self) -> SingleValueMapper<BigUint>;
+
     #[storage_set("unJailed")]
     fn set_unjailed(&self, bls_keys: &ManagedVec<BLSKey<Self::Api>>);
 }
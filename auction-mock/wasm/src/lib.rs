@@ -5,9 +5,9 @@
 ////////////////////////////////////////////////////
 
 // Init:                                 1
-// Endpoints:                           11
+// Endpoints:                           28
 // Async Callback (empty):               1
-// Total number of exported functions:  13
+// Total number of exported functions:  30
 
 #![no_std]
 
@@ -27,8 +27,25 @@ multiversx_sc_wasm_adapter::endpoints! {
         unBondTokens => unbond_tokens
         claim => claim
         unJail => unjail_endpoint
+        setBlsKeyStatus => set_bls_key_status_endpoint
+        getBlsKeysStatus => get_bls_keys_status
+        getTotalStakedTopUpStakedBlsKeys => get_total_staked_top_up_staked_bls_keys
+        setMalformedResults => set_malformed_results
         setBlsDeliberateError => set_bls_deliberate_error
         getBlsDeliberateError => get_bls_deliberate_error
+        setUnbondPeriod => set_unbond_period
+        setUnjailFinePerNode => set_unjail_fine_per_node
+        setSlashedStake => set_slashed_stake
+        setRewardPerNodePerEpoch => set_reward_per_node_per_epoch
+        jailNodes => jail_nodes
+        getAccruedRewards => get_accrued_rewards
+        collectRewards => collect_rewards
+        getNumCalls => get_num_calls
+        getCallHistory => get_call_history
+        getLastCall => get_last_call
+        getCallsToEndpoint => get_calls_to_endpoint
+        setEndpointFailureMode => set_endpoint_failure_mode
+        getEndpointFailureMode => get_endpoint_failure_mode
     )
 }
 
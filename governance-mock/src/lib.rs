@@ -0,0 +1,49 @@
+#![no_std]
+
+mod storage;
+
+multiversx_sc::imports!();
+
+/// Stands in for the governance system SC in tests.
+/// It records the votes it receives and can be set up to reject them.
+#[multiversx_sc::derive::contract]
+pub trait GovernanceMock: storage::GovernanceMockStorage {
+    #[init]
+    fn init(&self) {}
+
+    #[endpoint(delegateVote)]
+    fn delegate_vote(
+        &self,
+        proposal: u64,
+        vote: ManagedBuffer,
+        voter: ManagedAddress,
+        user_stake: BigUint,
+    ) {
+        let proposal_error = self.proposal_deliberate_error(proposal).get();
+        if !proposal_error.is_empty() {
+            sc_panic!(proposal_error);
+        }
+
+        let voter_error = self.voter_deliberate_error(&voter).get();
+        if !voter_error.is_empty() {
+            sc_panic!(voter_error);
+        }
+
+        let received_vote_mapper = self.received_vote(proposal, &voter);
+        require!(received_vote_mapper.is_empty(), "double vote");
+
+        received_vote_mapper.set(vote);
+        self.received_voting_power(proposal, &voter).set(user_stake);
+        self.num_received_votes(proposal).update(|n| *n += 1);
+    }
+
+    #[endpoint(setProposalDeliberateError)]
+    fn set_proposal_deliberate_error(&self, proposal: u64, error_message: ManagedBuffer) {
+        self.proposal_deliberate_error(proposal).set(error_message);
+    }
+
+    #[endpoint(setVoterDeliberateError)]
+    fn set_voter_deliberate_error(&self, voter: ManagedAddress, error_message: ManagedBuffer) {
+        self.voter_deliberate_error(&voter).set(error_message);
+    }
+}
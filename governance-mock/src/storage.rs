@@ -0,0 +1,32 @@
+multiversx_sc::imports!();
+
+#[multiversx_sc::derive::module]
+pub trait GovernanceMockStorage {
+    #[view(getReceivedVote)]
+    #[storage_mapper("received_vote")]
+    fn received_vote(
+        &self,
+        proposal: u64,
+        voter: &ManagedAddress,
+    ) -> SingleValueMapper<ManagedBuffer>;
+
+    #[view(getReceivedVotingPower)]
+    #[storage_mapper("received_voting_power")]
+    fn received_voting_power(
+        &self,
+        proposal: u64,
+        voter: &ManagedAddress,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[view(getNumReceivedVotes)]
+    #[storage_mapper("num_received_votes")]
+    fn num_received_votes(&self, proposal: u64) -> SingleValueMapper<usize>;
+
+    /// If not empty, all votes on the proposal fail with this error message.
+    #[storage_mapper("proposal_deliberate_error")]
+    fn proposal_deliberate_error(&self, proposal: u64) -> SingleValueMapper<ManagedBuffer>;
+
+    /// If not empty, all votes of the voter fail with this error message.
+    #[storage_mapper("voter_deliberate_error")]
+    fn voter_deliberate_error(&self, voter: &ManagedAddress) -> SingleValueMapper<ManagedBuffer>;
+}
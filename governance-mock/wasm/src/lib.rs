@@ -0,0 +1,30 @@
+// Code generated by the multiversx-sc build system. DO NOT EDIT.
+
+////////////////////////////////////////////////////
+////////////////// AUTO-GENERATED //////////////////
+////////////////////////////////////////////////////
+
+// Init:                                 1
+// Endpoints:                            6
+// Async Callback (empty):               1
+// Total number of exported functions:   8
+
+#![no_std]
+
+multiversx_sc_wasm_adapter::allocator!(leaking);
+multiversx_sc_wasm_adapter::panic_handler!();
+
+multiversx_sc_wasm_adapter::endpoints! {
+    governance_mock
+    (
+        init => init
+        delegateVote => delegate_vote
+        setProposalDeliberateError => set_proposal_deliberate_error
+        setVoterDeliberateError => set_voter_deliberate_error
+        getReceivedVote => received_vote
+        getReceivedVotingPower => received_voting_power
+        getNumReceivedVotes => num_received_votes
+    )
+}
+
+multiversx_sc_wasm_adapter::async_callback_empty! {}
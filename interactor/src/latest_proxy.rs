@@ -51,6 +51,7 @@ where
         Arg3: ProxyArg<u64>,
         Arg4: ProxyArg<BigUint<Env::Api>>,
         Arg5: ProxyArg<BigUint<Env::Api>>,
+        Arg6: ProxyArg<OptionalValue<u64>>,
     >(
         self,
         auction_contract_addr: Arg0,
@@ -59,6 +60,7 @@ where
         n_blocks_before_unbond: Arg3,
         minimum_stake: Arg4,
         total_delegation_cap: Arg5,
+        opt_bootstrap_deadline: Arg6,
     ) -> TxTypedDeploy<Env, From, NotPayable, Gas, ()> {
         self.wrapped_tx
             .payment(NotPayable)
@@ -69,6 +71,7 @@ where
             .argument(&n_blocks_before_unbond)
             .argument(&minimum_stake)
             .argument(&total_delegation_cap)
+            .argument(&opt_bootstrap_deadline)
             .original_result()
     }
 }
@@ -150,6 +153,19 @@ where
             .original_result()
     }
 
+    pub fn get_node_pending_since_endpoint<
+        Arg0: ProxyArg<node_storage::types::bls_key::BLSKey<Env::Api>>,
+    >(
+        self,
+        bls_key: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, OptionalValue<u64>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getNodePendingSince")
+            .argument(&bls_key)
+            .original_result()
+    }
+
     pub fn get_node_state_endpoint<
         Arg0: ProxyArg<node_storage::types::bls_key::BLSKey<Env::Api>>,
     >(
@@ -163,6 +179,43 @@ where
             .original_result()
     }
 
+    /// Number of nodes currently in the given state (by discriminant). 
+    pub fn get_num_nodes_by_state<
+        Arg0: ProxyArg<u8>,
+    >(
+        self,
+        state_discriminant: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, usize> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getNumNodesByState")
+            .argument(&state_discriminant)
+            .original_result()
+    }
+
+    /// Paginated list of the BLS keys of nodes in the given state (by discriminant). 
+    /// `from` is a 0-based position in the index, the order is not related to node ids. 
+    pub fn get_nodes_by_state<
+        Arg0: ProxyArg<u8>,
+        Arg1: ProxyArg<usize>,
+        Arg2: ProxyArg<usize>,
+    >(
+        self,
+        state_discriminant: Arg0,
+        from: Arg1,
+        count: Arg2,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, node_storage::types::bls_key::BLSKey<Env::Api>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getNodesByState")
+            .argument(&state_discriminant)
+            .argument(&from)
+            .argument(&count)
+            .original_result()
+    }
+
+    /// Lists every node ever added, including removed ones, in node id order. 
+    /// Prefer `getNodesByState` when only some states are of interest. 
     pub fn get_all_node_states(
         self,
     ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, MultiValue2<node_storage::types::bls_key::BLSKey<Env::Api>, u8>>> {
@@ -172,6 +225,55 @@ where
             .original_result()
     }
 
+    /// Configures the operator metadata of a node. The added-at nonce is kept. 
+    pub fn set_node_metadata<
+        Arg0: ProxyArg<node_storage::types::bls_key::BLSKey<Env::Api>>,
+        Arg1: ProxyArg<ManagedBuffer<Env::Api>>,
+        Arg2: ProxyArg<ManagedBuffer<Env::Api>>,
+        Arg3: ProxyArg<ManagedBuffer<Env::Api>>,
+        Arg4: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        bls_key: Arg0,
+        label: Arg1,
+        hosting_provider: Arg2,
+        region: Arg3,
+        operator: Arg4,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("setNodeMetadata")
+            .argument(&bls_key)
+            .argument(&label)
+            .argument(&hosting_provider)
+            .argument(&region)
+            .argument(&operator)
+            .original_result()
+    }
+
+    pub fn get_node_metadata_endpoint<
+        Arg0: ProxyArg<node_storage::types::bls_key::BLSKey<Env::Api>>,
+    >(
+        self,
+        bls_key: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, OptionalValue<node_storage::types::node_metadata::NodeMetadata<Env::Api>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getNodeMetadata")
+            .argument(&bls_key)
+            .original_result()
+    }
+
+    /// Same as `getAllNodeStates`, with the metadata of each node. 
+    pub fn get_all_node_states_with_metadata(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, MultiValue3<node_storage::types::bls_key::BLSKey<Env::Api>, u8, node_storage::types::node_metadata::NodeMetadata<Env::Api>>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getAllNodeStatesWithMetadata")
+            .original_result()
+    }
+
     pub fn get_node_bl_nonce_of_unstake_endpoint<
         Arg0: ProxyArg<node_storage::types::bls_key::BLSKey<Env::Api>>,
     >(
@@ -211,6 +313,45 @@ where
             .original_result()
     }
 
+    /// Replaces the stored signature of an inactive node, e.g. after a bad signature was registered. 
+    pub fn update_node_signature<
+        Arg0: ProxyArg<node_storage::types::bls_key::BLSKey<Env::Api>>,
+        Arg1: ProxyArg<node_storage::types::bls_sig::BLSSignature<Env::Api>>,
+    >(
+        self,
+        bls_key: Arg0,
+        bls_sig: Arg1,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("updateNodeSignature")
+            .argument(&bls_key)
+            .argument(&bls_sig)
+            .original_result()
+    }
+
+    /// Swaps the BLS key of an inactive node for a key that was never registered. 
+    /// The node keeps its id, so its state, metadata and queue positions are kept too. 
+    /// The old key is unregistered and can later be added again as a new node. 
+    pub fn replace_node_key<
+        Arg0: ProxyArg<node_storage::types::bls_key::BLSKey<Env::Api>>,
+        Arg1: ProxyArg<node_storage::types::bls_key::BLSKey<Env::Api>>,
+        Arg2: ProxyArg<node_storage::types::bls_sig::BLSSignature<Env::Api>>,
+    >(
+        self,
+        old_bls_key: Arg0,
+        new_bls_key: Arg1,
+        new_bls_sig: Arg2,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("replaceNodeKey")
+            .argument(&old_bls_key)
+            .argument(&new_bls_key)
+            .argument(&new_bls_sig)
+            .original_result()
+    }
+
     /// Each delegator gets a user id. This is in order to be able to iterate over their data. 
     /// This is a mapping from delegator address to delegator id. 
     /// The key is the bytes "user_id" concatenated with their public key. 
@@ -499,6 +640,128 @@ where
             .original_result()
     }
 
+    /// Stakes the next inactive nodes from the auto-stake queue, as many as the available funds allow. 
+    /// Each node is staked with the configured auto-stake amount per node. 
+    /// Can be called by anyone, since both the nodes and the amounts are decided by the owner. 
+    pub fn auto_stake_nodes(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("autoStakeNodes")
+            .original_result()
+    }
+
+    /// The amount each node is staked with by `autoStakeNodes`. 
+    /// Setting it to 0 disables automatic node staking. 
+    pub fn set_auto_stake_per_node<
+        Arg0: ProxyArg<BigUint<Env::Api>>,
+    >(
+        self,
+        stake_per_node: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("setAutoStakePerNode")
+            .argument(&stake_per_node)
+            .original_result()
+    }
+
+    /// Replaces the list of nodes that `autoStakeNodes` can stake, in the order in which they get staked. 
+    /// Nodes that are not inactive when their turn comes are skipped. 
+    pub fn set_auto_stake_queue<
+        Arg0: ProxyArg<MultiValueEncoded<Env::Api, node_storage::types::bls_key::BLSKey<Env::Api>>>,
+    >(
+        self,
+        bls_keys: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("setAutoStakeQueue")
+            .argument(&bls_keys)
+            .original_result()
+    }
+
+    pub fn get_auto_stake_queue(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, node_storage::types::bls_key::BLSKey<Env::Api>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getAutoStakeQueue")
+            .original_result()
+    }
+
+    pub fn auto_stake_per_node(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, BigUint<Env::Api>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getAutoStakePerNode")
+            .original_result()
+    }
+
+    /// Sends unprotected funds to the auction SC as top-up for the already active nodes, 
+    /// without staking new nodes. 
+    pub fn stake_top_up<
+        Arg0: ProxyArg<BigUint<Env::Api>>,
+    >(
+        self,
+        amount: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("stakeTopUp")
+            .argument(&amount)
+            .original_result()
+    }
+
+    /// Total EGLD sent to the auction SC as top-up via `stakeTopUp`. 
+    /// See `syncAuctionStake` for the top-up as seen by the auction SC. 
+    pub fn total_top_up(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, BigUint<Env::Api>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getTotalTopUp")
+            .original_result()
+    }
+
+    /// Unstakes part of the top-up from the auction SC. 
+    /// It can be reclaimed with `unBondTopUp` once the unbond period has passed. 
+    pub fn unstake_top_up<
+        Arg0: ProxyArg<BigUint<Env::Api>>,
+    >(
+        self,
+        amount: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("unStakeTopUp")
+            .argument(&amount)
+            .original_result()
+    }
+
+    /// Reclaims all the unstaked top-up from the auction SC. 
+    /// The funds return to the contract as unprotected funds. 
+    pub fn unbond_top_up(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("unBondTopUp")
+            .original_result()
+    }
+
+    /// Top-up unstaked from the auction SC, by `unStakeTopUp` or by the sunset, not yet reclaimed. 
+    pub fn unstaked_top_up(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, BigUint<Env::Api>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getUnStakedTopUp")
+            .original_result()
+    }
+
     /// Unstakes from the auction smart contract. 
     /// The nodes will stop receiving rewards, but stake cannot be yet reclaimed. 
     /// This operation is performed by the owner. 
@@ -573,6 +836,85 @@ where
             .original_result()
     }
 
+    /// Number of blocks after which a node in a pending state is considered stuck, 
+    /// i.e. the auction callback was most likely lost. 
+    pub fn set_pending_node_timeout<
+        Arg0: ProxyArg<u64>,
+    >(
+        self,
+        timeout_blocks: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("setPendingNodeTimeout")
+            .argument(&timeout_blocks)
+            .original_result()
+    }
+
+    pub fn pending_node_timeout(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, u64> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getPendingNodeTimeout")
+            .original_result()
+    }
+
+    /// Moves nodes stuck in a pending state for longer than the timeout out of it. 
+    /// The auction SC is queried for the status of our BLS keys, which decides where each node goes: 
+    /// - staked, jailed, queued or in the auction: `Active`, 
+    /// - unstaked: `UnBondPeriod`, counted from the unstake call for `PendingUnBond` and `PendingDeactivation`, 
+    /// - unknown to the auction SC: `Inactive`. 
+    pub fn recover_stuck_nodes<
+        Arg0: ProxyArg<MultiValueManagedVec<Env::Api, node_storage::types::bls_key::BLSKey<Env::Api>>>,
+    >(
+        self,
+        bls_keys: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("recoverStuckNodes")
+            .argument(&bls_keys)
+            .original_result()
+    }
+
+    /// Queries the auction SC for the status of our BLS keys 
+    /// and corrects the local states of the given nodes where they drifted from it. 
+    /// Nodes in a pending state are left alone, their callback might still be on the way. 
+    /// Providers with more nodes than fit in one call reconcile them in batches. 
+    pub fn reconcile_node_states<
+        Arg0: ProxyArg<MultiValueManagedVec<Env::Api, node_storage::types::bls_key::BLSKey<Env::Api>>>,
+    >(
+        self,
+        bls_keys: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("reconcileNodeStates")
+            .argument(&bls_keys)
+            .original_result()
+    }
+
+    /// Queries the auction SC for the total stake and top-up of the contract. 
+    /// The result can be retrieved with `getAuctionStakeReport`. 
+    pub fn sync_auction_stake(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("syncAuctionStake")
+            .original_result()
+    }
+
+    pub fn auction_stake_report(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, AuctionStakeReport<Env::Api>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getAuctionStakeReport")
+            .original_result()
+    }
+
     /// Claims from auction SC funds that were sent but are not required to run the nodes. 
     pub fn claim_unused_funds(
         self,
@@ -595,6 +937,24 @@ where
             .original_result()
     }
 
+    /// Same as `unJailNodes`, but the fine is paid from the unclaimed rewards of the owner, 
+    /// instead of a fresh payment. 
+    pub fn unjail_nodes_from_rewards<
+        Arg0: ProxyArg<BigUint<Env::Api>>,
+        Arg1: ProxyArg<MultiValueManagedVec<Env::Api, node_storage::types::bls_key::BLSKey<Env::Api>>>,
+    >(
+        self,
+        fine_amount: Arg0,
+        bls_keys: Arg1,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("unJailNodesFromRewards")
+            .argument(&fine_amount)
+            .argument(&bls_keys)
+            .original_result()
+    }
+
     pub fn unstake_tokens<
         Arg0: ProxyArg<BigUint<Env::Api>>,
     >(
@@ -621,6 +981,19 @@ where
             .original_result()
     }
 
+    pub fn get_node_jail_history_endpoint<
+        Arg0: ProxyArg<node_storage::types::bls_key::BLSKey<Env::Api>>,
+    >(
+        self,
+        bls_key: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, OptionalValue<NodeJailHistory<Env::Api>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getNodeJailHistory")
+            .argument(&bls_key)
+            .original_result()
+    }
+
     /// Yields the address of the contract with which staking will be performed. 
     /// This address is standard in the protocol, but it is saved in storage to avoid hardcoding it. 
     pub fn get_auction_contract_address(
@@ -661,6 +1034,41 @@ where
             .original_result()
     }
 
+    /// Epoch from which delegators can reclaim their stake if the total delegation cap was not filled. 
+    /// 0 means no deadline. 
+    pub fn bootstrap_deadline(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, u64> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getBootstrapDeadline")
+            .original_result()
+    }
+
+    /// Only possible in bootstrap mode. 
+    /// Once set, the deadline can only be brought forward, so that delegators can rely on it. 
+    pub fn set_bootstrap_deadline_endpoint<
+        Arg0: ProxyArg<u64>,
+    >(
+        self,
+        deadline_epoch: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("setBootstrapDeadline")
+            .argument(&deadline_epoch)
+            .original_result()
+    }
+
+    pub fn is_bootstrap_deadline_passed(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, bool> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("isBootstrapDeadlinePassed")
+            .original_result()
+    }
+
     /// The minimum proportion of stake that has to be provided by the owner. 
     /// 10000 = 100%. 
     pub fn get_owner_min_stake_share(
@@ -719,7 +1127,27 @@ where
             .original_result()
     }
 
-    pub fn global_op_checkpoint(
+    /// How Active stake is forced out when the total delegation cap decreases. 
+    pub fn forced_unstake_mode(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ForcedUnStakeMode> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getForcedUnStakeMode")
+            .original_result()
+    }
+
+    /// How Waiting stake is activated when there is room for more Active stake. 
+    pub fn waiting_activation_mode(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, WaitingActivationMode<Env::Api>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getWaitingActivationMode")
+            .original_result()
+    }
+
+    pub fn global_op_checkpoint(
         self,
     ) -> TxTypedCall<Env, From, To, NotPayable, Gas, GlobalOpCheckpoint<Env::Api>> {
         self.wrapped_tx
@@ -833,6 +1261,53 @@ where
             .original_result()
     }
 
+    /// If the total delegation cap was not filled by the bootstrap deadline, 
+    /// the owner can lower it to what was raised, which ends bootstrap mode. 
+    pub fn lower_delegation_cap_to_raised(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, OperationCompletionStatus> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("lowerDelegationCapToRaised")
+            .original_result()
+    }
+
+    /// Selects how Active stake is forced out when the total delegation cap decreases: 
+    /// - `LastInFirstOut` (default): the most recently activated stake goes first, 
+    /// - `ProRata`: all delegators lose the same proportion of their Active stake. 
+    pub fn set_forced_unstake_mode_endpoint<
+        Arg0: ProxyArg<ForcedUnStakeMode>,
+    >(
+        self,
+        mode: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("setForcedUnStakeMode")
+            .argument(&mode)
+            .original_result()
+    }
+
+    /// Selects how Waiting stake is activated when the total delegation cap increases: 
+    /// - `FirstInFirstOut` (default): the oldest Waiting stake is activated first, 
+    /// - `ProRata`: all Waiting stake is activated in the same proportion, 
+    /// - `CappedPerUser`: each user gets at most `max_per_user` activated in one go. 
+    ///  
+    /// Waiting stake replacing stake unstaked by users is always activated first-in-first-out, 
+    /// so that stake & unstake only ever touch a bounded part of the waiting list. 
+    pub fn set_waiting_activation_mode_endpoint<
+        Arg0: ProxyArg<WaitingActivationMode<Env::Api>>,
+    >(
+        self,
+        mode: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("setWaitingActivationMode")
+            .argument(&mode)
+            .original_result()
+    }
+
     /// The stake per node can be changed by the owner. 
     /// It does not get set in the constructor, so the owner has to manually set it after the contract is deployed. 
     pub fn set_service_fee_endpoint<
@@ -886,6 +1361,19 @@ where
             .original_result()
     }
 
+    /// If the total delegation cap was not filled by the bootstrap deadline, 
+    /// delegators can take back all their Waiting and Active stake. 
+    /// It becomes WithdrawOnly right away, and can be withdrawn with `unBond`. 
+    /// Active stake that the owner already staked in nodes cannot be reclaimed this way. 
+    pub fn reclaim_bootstrap_stake(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, BigUint<Env::Api>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("reclaimBootstrapStake")
+            .original_result()
+    }
+
     pub fn get_unstakeable<
         Arg0: ProxyArg<ManagedAddress<Env::Api>>,
     >(
@@ -921,6 +1409,40 @@ where
             .original_result()
     }
 
+    /// Lists all deferred payment tranches of a user, in the order in which they are paid out. 
+    /// For each tranche it yields: amount, number of blocks remaining until it can be unbonded, 
+    /// and the block nonce from which `unBond` will pay it out. 
+    /// Estimates are based on the current `n_blocks_before_unbond` setting. 
+    pub fn get_user_unbond_eta<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        user_address: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, MultiValue3<BigUint<Env::Api>, u64, u64>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getUserUnBondEta")
+            .argument(&user_address)
+            .original_result()
+    }
+
+    /// Yields the amount the user will be able to unbond next, and the block nonce from which it is possible. 
+    /// If something can already be unbonded, it yields the currently unbondable amount and the current block nonce. 
+    /// Otherwise, it yields the sum of the tranches that become eligible first, and their claim nonce. 
+    /// Yields (0, 0) if there is nothing pending. 
+    pub fn get_user_next_unbond<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        user_address: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValue2<BigUint<Env::Api>, u64>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getUserNextUnBond")
+            .argument(&user_address)
+            .original_result()
+    }
+
     /// Raw id of the last checkpoint reached by any of the dust cleanup endpoints. 
     pub fn dust_cleanup_checkpoint(
         self,
@@ -998,6 +1520,234 @@ where
             .original_result()
     }
 
+    /// Outcome of the last completed audit: passed, or the first violation found. 
+    pub fn get_accounting_audit_result(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, AccountingAuditResult<Env::Api>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getAccountingAuditResult")
+            .original_result()
+    }
+
+    /// Verifies that: 
+    /// - every fund list is well formed and its total balance is the sum of its items, 
+    /// - for each type, the user lists add up to the type list, 
+    /// - the EGLD balance covers the unclaimed rewards, plus the Waiting, DeferredPayment and WithdrawOnly funds. 
+    ///  
+    /// Will stop if running low on gas, and continue from where it left off when called again. 
+    /// Any change to the funds, incoming rewards or any reward payout in between calls starts the audit over. 
+    /// Stops at the first violation found. 
+    pub fn audit_accounting(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, OperationCompletionStatus> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("auditAccounting")
+            .original_result()
+    }
+
+    pub fn emergency_mode(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, bool> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("isEmergencyMode")
+            .original_result()
+    }
+
+    /// The invariant found violated when emergency mode was triggered. 
+    pub fn emergency_mode_reason(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ManagedBuffer<Env::Api>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getEmergencyModeReason")
+            .original_result()
+    }
+
+    /// Can be called by anyone. Only succeeds if one of the invariants is currently violated. 
+    pub fn trigger_emergency_mode(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("triggerEmergencyMode")
+            .original_result()
+    }
+
+    /// Lifts emergency mode, once the invariants hold again. 
+    pub fn exit_emergency_mode(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("exitEmergencyMode")
+            .original_result()
+    }
+
+    /// Previews `modifyTotalDelegationCap`. 
+    /// For each affected user it yields: the user address, and the amounts that would be moved 
+    /// Waiting -> Active, UnStaked -> DeferredPayment and Active -> DeferredPayment. 
+    /// Takes the current waiting activation mode and forced unstake mode into account. 
+    pub fn simulate_modify_total_delegation_cap<
+        Arg0: ProxyArg<BigUint<Env::Api>>,
+    >(
+        self,
+        new_total_cap: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, MultiValue4<ManagedAddress<Env::Api>, BigUint<Env::Api>, BigUint<Env::Api>, BigUint<Env::Api>>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("simulateModifyTotalDelegationCap")
+            .argument(&new_total_cap)
+            .original_result()
+    }
+
+    /// Previews `unStake` for a user. 
+    /// Yields the stake of the user after the operation, by type: 
+    /// withdraw only, waiting, active, unstaked, deferred payment, 
+    /// followed by the rewards the user can claim. 
+    /// Waiting stake replacing unstaked stake is activated first-in-first-out in every waiting activation mode, 
+    /// so the swap to deferred payment shows up right away, as it does in `unStake`. 
+    pub fn simulate_unstake<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+        Arg1: ProxyArg<BigUint<Env::Api>>,
+    >(
+        self,
+        user_address: Arg0,
+        amount: Arg1,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValue6<BigUint<Env::Api>, BigUint<Env::Api>, BigUint<Env::Api>, BigUint<Env::Api>, BigUint<Env::Api>, BigUint<Env::Api>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("simulateUnstake")
+            .argument(&user_address)
+            .argument(&amount)
+            .original_result()
+    }
+
+    /// Previews how a new service fee changes the way rewards are split. 
+    /// For each user entitled to rewards it yields: the user address, 
+    /// the user share with the current service fee and the user share with the new service fee. 
+    /// Shares are computed out of `reward_amount`, based on the current Active stake. 
+    /// If `reward_amount` is missing, all the rewards received so far are used instead. 
+    /// Rewards already computed are not affected by a service fee change. 
+    pub fn simulate_service_fee<
+        Arg0: ProxyArg<usize>,
+        Arg1: ProxyArg<OptionalValue<BigUint<Env::Api>>>,
+    >(
+        self,
+        service_fee_per_10000: Arg0,
+        opt_reward_amount: Arg1,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, MultiValue3<ManagedAddress<Env::Api>, BigUint<Env::Api>, BigUint<Env::Api>>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("simulateServiceFee")
+            .argument(&service_fee_per_10000)
+            .argument(&opt_reward_amount)
+            .original_result()
+    }
+
+    pub fn is_sunset(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, bool> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("isSunset")
+            .original_result()
+    }
+
+    /// Irreversibly starts winding down the contract, by unstaking the first batch of Active nodes. 
+    /// Neither the pause nor emergency mode hold back the sunset. 
+    pub fn start_sunset(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("startSunset")
+            .original_result()
+    }
+
+    /// Moves the sunset forward, as far as currently possible: 
+    /// - unstakes the next batch of Active nodes, then the top-up, then 
+    /// - unbonds the nodes whose unbond period passed, then the top-up, then 
+    /// - converts all Waiting, Active and UnStaked stake to DeferredPayment. 
+    ///  
+    /// Node operations end the transaction, and `continueSunset` has to be called again once their callback arrived. 
+    /// Converting the stake is a global operation, that can also be continued with `continueGlobalOperation`. 
+    ///  
+    /// If stake was lost, for instance to a jail fine, the balance does not cover all the stake. 
+    /// Users are then paid in full in the order they `unBond`, until the balance runs out. 
+    pub fn continue_sunset(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, OperationCompletionStatus> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("continueSunset")
+            .original_result()
+    }
+
+    pub fn get_sunset_status(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, SunsetStatus<Env::Api>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getSunsetStatus")
+            .original_result()
+    }
+
+    /// Number of epochs UnStaked stake can wait before anyone can use the escape hatch. 
+    /// 0 disables the escape hatch. 
+    /// While UnStaked stake is waiting, the delay can only be shortened. 
+    pub fn set_escape_hatch_epochs<
+        Arg0: ProxyArg<u64>,
+    >(
+        self,
+        epochs: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("setEscapeHatchEpochs")
+            .argument(&epochs)
+            .original_result()
+    }
+
+    pub fn escape_hatch_epochs(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, u64> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getEscapeHatchEpochs")
+            .original_result()
+    }
+
+    /// The epoch from which the escape hatch can be used, based on the oldest UnStaked stake. 
+    /// Yields nothing if the escape hatch is disabled or there is no UnStaked stake. 
+    pub fn get_escape_hatch_epoch(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, OptionalValue<u64>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getEscapeHatchEpoch")
+            .original_result()
+    }
+
+    /// Can be called by anyone, once the oldest UnStaked stake waited for too long, even while the contract is paused. 
+    /// Each call performs the next step: 
+    /// - if the contract has enough free funds, lowers the cap by the UnStaked amount, 
+    ///   which converts all UnStaked stake to deferred payment, as a global operation; 
+    /// - otherwise, if nodes are in the unbond period, unbonds them; 
+    ///   the auction SC rejects the nodes whose unbond period did not pass yet; 
+    /// - otherwise, unstakes as many Active nodes as needed to cover the UnStaked amount, together with their tokens, 
+    ///   counting the nodes already being unstaked or unbonded. 
+    pub fn escape_hatch(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, OperationCompletionStatus> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("escapeHatch")
+            .original_result()
+    }
+
     pub fn dns_register<
         Arg0: ProxyArg<ManagedAddress<Env::Api>>,
         Arg1: ProxyArg<ManagedBuffer<Env::Api>>,
@@ -1056,6 +1806,16 @@ where
             .original_result()
     }
 
+    /// Forwards the vote of the caller to the governance system SC, weighted by their active stake. 
+    /// Each user can vote only once per proposal. 
+    /// The vote is recorded only after the governance system SC accepts it. 
+    ///  
+    /// The voting power is snapshotted when voting, and that much Active stake stays locked 
+    /// until voting on the proposal ends, so the same stake cannot be moved to another address and vote again. 
+    /// Only proposals registered with `registerProposal` can be voted on, so that the end of the lock is known. 
+    ///  
+    /// Representatives also vote for all the users who assigned them their voting power, 
+    /// unless these already voted on the proposal. 
     pub fn delegate_vote<
         Arg0: ProxyArg<u64>,
         Arg1: ProxyArg<ManagedBuffer<Env::Api>>,
@@ -1072,6 +1832,88 @@ where
             .original_result()
     }
 
+    /// Assigns the voting power of the caller to a representative, who will vote on their behalf. 
+    /// Representatives cannot assign their own voting power further, 
+    /// and represent at most `MAX_REPRESENTED_USERS` users. 
+    pub fn assign_representative<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        representative: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("assignRepresentative")
+            .argument(&representative)
+            .original_result()
+    }
+
+    /// The caller takes back their voting power from their representative. 
+    pub fn revoke_representative(
+        self,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("revokeRepresentative")
+            .original_result()
+    }
+
+    /// Voting power a representative would vote with on a proposal: their own Active stake, 
+    /// plus the Active stake of all users who assigned them their voting power and did not vote on it themselves. 
+    pub fn get_representative_voting_power<
+        Arg0: ProxyArg<u64>,
+        Arg1: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        proposal: Arg0,
+        representative: Arg1,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, BigUint<Env::Api>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getRepresentativeVotingPower")
+            .argument(&proposal)
+            .argument(&representative)
+            .original_result()
+    }
+
+    /// Schedules a vote on behalf of all delegators who do not vote themselves. 
+    /// Delegators can still vote with `delegateVote` until the deadline nonce, 
+    /// after which the pool vote can be cast with `castPoolVote`. 
+    /// Can be changed as long as it was not cast. 
+    pub fn set_pool_vote<
+        Arg0: ProxyArg<u64>,
+        Arg1: ProxyArg<ManagedBuffer<Env::Api>>,
+        Arg2: ProxyArg<u64>,
+    >(
+        self,
+        proposal: Arg0,
+        vote: Arg1,
+        deadline_nonce: Arg2,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("setPoolVote")
+            .argument(&proposal)
+            .argument(&vote)
+            .argument(&deadline_nonce)
+            .original_result()
+    }
+
+    /// Forwards the pool vote to the governance system SC, once the deadline has passed. 
+    /// It is weighted by the total Active stake, minus the voting power of the delegators who voted themselves. 
+    pub fn cast_pool_vote<
+        Arg0: ProxyArg<u64>,
+    >(
+        self,
+        proposal: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("castPoolVote")
+            .argument(&proposal)
+            .original_result()
+    }
+
     /// Voting power of a single user, based on their active stake. 
     pub fn get_voting_power<
         Arg0: ProxyArg<ManagedAddress<Env::Api>>,
@@ -1085,6 +1927,170 @@ where
             .argument(&voter)
             .original_result()
     }
+
+    /// Registers the block nonce at which voting on a proposal ends. 
+    /// Delegators can only vote on registered proposals, and their votes lock the voting power until then. 
+    /// Once registered, the end nonce can only be brought forward. 
+    pub fn register_proposal<
+        Arg0: ProxyArg<u64>,
+        Arg1: ProxyArg<u64>,
+    >(
+        self,
+        proposal: Arg0,
+        end_nonce: Arg1,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ()> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("registerProposal")
+            .argument(&proposal)
+            .argument(&end_nonce)
+            .original_result()
+    }
+
+    /// Active stake of a user that cannot be unstaked, because it voted on proposals still open. 
+    /// The same stake can vote on several proposals, so the largest lock applies. 
+    pub fn get_locked_voting_power<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        voter: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, BigUint<Env::Api>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getLockedVotingPower")
+            .argument(&voter)
+            .original_result()
+    }
+
+    /// Yields the vote of a user on a proposal, together with the voting power it was cast with. 
+    /// Yields nothing if the user did not vote on the proposal through this contract. 
+    pub fn get_user_vote<
+        Arg0: ProxyArg<u64>,
+        Arg1: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        proposal: Arg0,
+        voter: Arg1,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, OptionalValue<MultiValue2<ManagedBuffer<Env::Api>, BigUint<Env::Api>>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getUserVote")
+            .argument(&proposal)
+            .argument(&voter)
+            .original_result()
+    }
+
+    /// Yields the voting power cast through this contract on a proposal, summed per vote option. 
+    pub fn get_proposal_tally<
+        Arg0: ProxyArg<u64>,
+    >(
+        self,
+        proposal: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, MultiValue2<ManagedBuffer<Env::Api>, BigUint<Env::Api>>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getProposalTally")
+            .argument(&proposal)
+            .original_result()
+    }
+
+    pub fn proposal_end_nonce<
+        Arg0: ProxyArg<u64>,
+    >(
+        self,
+        proposal: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, u64> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getProposalEndNonce")
+            .argument(&proposal)
+            .original_result()
+    }
+
+    pub fn representative<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        user: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, ManagedAddress<Env::Api>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getRepresentative")
+            .argument(&user)
+            .original_result()
+    }
+
+    pub fn represented_users<
+        Arg0: ProxyArg<ManagedAddress<Env::Api>>,
+    >(
+        self,
+        representative: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, MultiValueEncoded<Env::Api, ManagedAddress<Env::Api>>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getRepresentedUsers")
+            .argument(&representative)
+            .original_result()
+    }
+
+    pub fn pool_vote<
+        Arg0: ProxyArg<u64>,
+    >(
+        self,
+        proposal: Arg0,
+    ) -> TxTypedCall<Env, From, To, NotPayable, Gas, PoolVote<Env::Api>> {
+        self.wrapped_tx
+            .payment(NotPayable)
+            .raw_call("getPoolVote")
+            .argument(&proposal)
+            .original_result()
+    }
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, PartialEq, Debug)]
+pub struct AuctionStakeReport<Api>
+where
+    Api: ManagedTypeApi,
+{
+    pub top_up: BigUint<Api>,
+    pub total_staked: BigUint<Api>,
+    pub num_staked_nodes: u64,
+    pub reported_at: u64,
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone, Debug)]
+pub struct NodeJailHistory<Api>
+where
+    Api: ManagedTypeApi,
+{
+    pub jailed: bool,
+    pub jail_count: u32,
+    pub unjail_count: u32,
+    pub last_unjail_nonce: u64,
+    pub total_fines_paid: BigUint<Api>,
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone, Copy, Debug)]
+pub enum ForcedUnStakeMode {
+    LastInFirstOut,
+    ProRata,
+}
+
+#[rustfmt::skip]
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone, Debug)]
+pub enum WaitingActivationMode<Api>
+where
+    Api: ManagedTypeApi,
+{
+    FirstInFirstOut,
+    ProRata,
+    CappedPerUser {
+        max_per_user: BigUint<Api>,
+    },
 }
 
 #[rustfmt::skip]
@@ -1100,6 +2106,7 @@ where
         new_service_fee: BigUint<Api>,
         compute_rewards_data: ComputeAllRewardsData<Api>,
     },
+    Sunset(SunsetStep<Api>),
 }
 
 #[type_abi]
@@ -1125,6 +2132,9 @@ where
     SwapWaitingToActive,
     SwapUnstakedToDeferredPayment,
     SwapActiveToDeferredPayment,
+    SwapActiveToDeferredPaymentProRata(ProRataSwapData<Api>),
+    SwapWaitingToActiveProRata(ProRataSwapData<Api>),
+    SwapWaitingToActiveCappedPerUser(CappedPerUserSwapData<Api>),
 }
 
 #[type_abi]
@@ -1137,3 +2147,107 @@ where
     pub sum_unclaimed: BigUint<Api>,
     pub rewards_checkpoint: BigUint<Api>,
 }
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub struct ProRataSwapData<Api>
+where
+    Api: ManagedTypeApi,
+{
+    pub total_swap: BigUint<Api>,
+    pub total_source: BigUint<Api>,
+    pub current_id: usize,
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub struct CappedPerUserSwapData<Api>
+where
+    Api: ManagedTypeApi,
+{
+    pub max_per_user: BigUint<Api>,
+    pub user_ids: ManagedVec<Api, usize>,
+    pub next_index: usize,
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub enum SunsetStep<Api>
+where
+    Api: ManagedTypeApi,
+{
+    ComputeAllRewards(ComputeAllRewardsData<Api>),
+    SwapWaitingToDeferredPayment,
+    SwapUnstakedToDeferredPayment,
+    SwapActiveToDeferredPayment,
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone, Copy, Debug)]
+pub enum SunsetPhase {
+    NotStarted,
+    UnStakingNodes,
+    UnBondingNodes,
+    ConvertingStake,
+    Withdrawing,
+}
+
+#[rustfmt::skip]
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub enum AccountingAuditResult<Api>
+where
+    Api: ManagedTypeApi,
+{
+    NotRun,
+    Passed {
+        block_nonce: u64,
+    },
+    Violation(AccountingViolation<Api>),
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub struct AccountingViolation<Api>
+where
+    Api: ManagedTypeApi,
+{
+    pub reason: ManagedBuffer<Api>,
+    pub type_index: usize,
+    pub user_id: usize,
+    pub fund_id: usize,
+    pub block_nonce: u64,
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, PartialEq, Debug)]
+pub struct SunsetStatus<Api>
+where
+    Api: ManagedTypeApi,
+{
+    pub phase: SunsetPhase,
+    pub num_nodes_to_unstake: usize,
+    pub num_nodes_to_unbond: usize,
+    pub stake_to_convert: BigUint<Api>,
+    pub stake_to_withdraw: BigUint<Api>,
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone, Debug)]
+pub struct PoolVote<Api>
+where
+    Api: ManagedTypeApi,
+{
+    pub vote: ManagedBuffer<Api>,
+    pub deadline_nonce: u64,
+    pub status: PoolVoteStatus,
+    pub voting_power: BigUint<Api>,
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone, Copy, Debug)]
+pub enum PoolVoteStatus {
+    Scheduled,
+    Pending,
+    Cast,
+}
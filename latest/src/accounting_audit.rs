@@ -0,0 +1,356 @@
+use crate::accounting_audit_types::{
+    AccountingAuditData, AccountingAuditResult, AccountingAuditStep, AccountingViolation,
+    FundListWalk,
+};
+use core::num::NonZeroUsize;
+use user_fund_storage::fund_view_module::USER_STAKE_TOTALS_ID;
+use user_fund_storage::types::FundType;
+
+multiversx_sc::imports!();
+
+pub const AUDIT_GASLIMIT: u64 = 20_000_000;
+
+/// Outcome of running one audit step.
+enum AuditStepOutcome<M: ManagedTypeApi> {
+    Interrupted(AccountingAuditStep<M>),
+    Next(AccountingAuditStep<M>),
+    Finished(AccountingAuditResult<M>),
+}
+
+/// What can be wrong with a single fund list.
+enum FundListViolation {
+    WrongFundType,
+    WrongUserId,
+    BrokenPrev,
+    BrokenLast,
+    SumMismatch,
+}
+
+impl FundListViolation {
+    fn reason(&self, by_user: bool) -> &'static [u8] {
+        if by_user {
+            match self {
+                FundListViolation::WrongFundType => b"user-type list inconsistency: bad fund type",
+                FundListViolation::WrongUserId => b"user-type list inconsistency: bad user_id",
+                FundListViolation::BrokenPrev => b"user-type list inconsistency: bad prev",
+                FundListViolation::BrokenLast => b"user-type list inconsistency: bad list last",
+                FundListViolation::SumMismatch => b"user-type list inconsistency: bad sum",
+            }
+        } else {
+            match self {
+                FundListViolation::WrongFundType => b"type list inconsistency: bad fund type",
+                FundListViolation::WrongUserId => b"type list inconsistency: bad user_id",
+                FundListViolation::BrokenPrev => b"type list inconsistency: bad prev",
+                FundListViolation::BrokenLast => b"type list inconsistency: bad last",
+                FundListViolation::SumMismatch => b"type list inconsistency: bad sum",
+            }
+        }
+    }
+}
+
+/// Checks, over as many transactions as needed, that the fund storage is consistent
+/// and that the contract holds enough EGLD to pay out everything it owes.
+#[multiversx_sc::derive::module]
+pub trait AccountingAuditModule:
+    crate::rewards_state::RewardStateModule
+    + crate::settings::SettingsModule
+    + user_fund_storage::user_data::UserDataModule
+    + user_fund_storage::fund_module::FundModule
+    + user_fund_storage::fund_view_module::FundViewModule
+{
+    #[storage_mapper("accounting_audit_checkpoint")]
+    fn accounting_audit_checkpoint(&self) -> SingleValueMapper<AccountingAuditData<Self::Api>>;
+
+    #[storage_mapper("accounting_audit_result")]
+    fn accounting_audit_result(&self) -> SingleValueMapper<AccountingAuditResult<Self::Api>>;
+
+    /// Outcome of the last completed audit: passed, or the first violation found.
+    #[view(getAccountingAuditResult)]
+    fn get_accounting_audit_result(&self) -> AccountingAuditResult<Self::Api> {
+        let mapper = self.accounting_audit_result();
+        if mapper.is_empty() {
+            AccountingAuditResult::NotRun
+        } else {
+            mapper.get()
+        }
+    }
+
+    /// Verifies that:
+    /// - every fund list is well formed and its total balance is the sum of its items,
+    /// - for each type, the user lists add up to the type list,
+    /// - the EGLD balance covers the unclaimed rewards, plus the Waiting, DeferredPayment and WithdrawOnly funds.
+    ///
+    /// Will stop if running low on gas, and continue from where it left off when called again.
+    /// Any change to the funds, incoming rewards or any reward payout in between calls starts the audit over.
+    /// Stops at the first violation found.
+    #[endpoint(auditAccounting)]
+    fn audit_accounting(&self) -> OperationCompletionStatus {
+        let mut data = self.load_or_start_accounting_audit();
+        loop {
+            match self.continue_accounting_audit_step(data.step) {
+                AuditStepOutcome::Interrupted(step) => {
+                    data.step = step;
+                    self.accounting_audit_checkpoint().set(data);
+                    return OperationCompletionStatus::InterruptedBeforeOutOfGas;
+                }
+                AuditStepOutcome::Next(step) => {
+                    data.step = step;
+                }
+                AuditStepOutcome::Finished(result) => {
+                    self.accounting_audit_checkpoint().clear();
+                    self.accounting_audit_result().set(result);
+                    return OperationCompletionStatus::Completed;
+                }
+            }
+        }
+    }
+
+    fn load_or_start_accounting_audit(&self) -> AccountingAuditData<Self::Api> {
+        let fund_max_id = self.get_fund_max_id();
+        let type_totals = self.get_fund_type_totals();
+        let total_cumulated_rewards = self.get_total_cumulated_rewards();
+        let sent_rewards = self.get_sent_rewards();
+
+        let checkpoint_mapper = self.accounting_audit_checkpoint();
+        if !checkpoint_mapper.is_empty() {
+            let data = checkpoint_mapper.get();
+            if data.fund_max_id == fund_max_id
+                && data.type_totals == type_totals
+                && data.total_cumulated_rewards == total_cumulated_rewards
+                && data.sent_rewards == sent_rewards
+            {
+                return data;
+            }
+        }
+
+        AccountingAuditData {
+            fund_max_id,
+            type_totals,
+            total_cumulated_rewards,
+            sent_rewards,
+            step: AccountingAuditStep::TypeList {
+                type_index: 0,
+                walk: FundListWalk::new(),
+            },
+        }
+    }
+
+    /// The total balance of each type list, in the order of `FundType::ALL_TYPES`.
+    fn get_fund_type_totals(&self) -> ManagedVec<BigUint> {
+        let mut type_totals = ManagedVec::new();
+        for fund_type in FundType::ALL_TYPES.iter() {
+            type_totals.push(self.get_fund_list_by_type(*fund_type).total_balance);
+        }
+        type_totals
+    }
+
+    fn continue_accounting_audit_step(
+        &self,
+        step: AccountingAuditStep<Self::Api>,
+    ) -> AuditStepOutcome<Self::Api> {
+        match step {
+            AccountingAuditStep::TypeList {
+                type_index,
+                mut walk,
+            } => match self.walk_fund_list(&mut walk, type_index, None) {
+                Ok(false) => {
+                    AuditStepOutcome::Interrupted(AccountingAuditStep::TypeList { type_index, walk })
+                }
+                Ok(true) => AuditStepOutcome::Next(AccountingAuditStep::UserLists {
+                    type_index,
+                    user_id: 1,
+                    walk: FundListWalk::new(),
+                    sum_user_totals: BigUint::zero(),
+                }),
+                Err((violation, fund_id)) => {
+                    self.accounting_violation(violation.reason(false), type_index, 0, fund_id)
+                }
+            },
+            AccountingAuditStep::UserLists {
+                type_index,
+                user_id,
+                walk,
+                sum_user_totals,
+            } => self.audit_user_lists(type_index, user_id, walk, sum_user_totals),
+            AccountingAuditStep::UnclaimedRewards {
+                next_user_id,
+                sum_unclaimed,
+            } => self.audit_unclaimed_rewards(next_user_id, sum_unclaimed),
+        }
+    }
+
+    fn audit_user_lists(
+        &self,
+        type_index: usize,
+        mut user_id: usize,
+        mut walk: FundListWalk<Self::Api>,
+        mut sum_user_totals: BigUint,
+    ) -> AuditStepOutcome<Self::Api> {
+        let num_users = self.get_num_users();
+        while user_id <= num_users {
+            match self.walk_fund_list(&mut walk, type_index, Some(user_id)) {
+                Ok(false) => {
+                    return AuditStepOutcome::Interrupted(AccountingAuditStep::UserLists {
+                        type_index,
+                        user_id,
+                        walk,
+                        sum_user_totals,
+                    });
+                }
+                Ok(true) => {
+                    sum_user_totals += &walk.sum;
+                    walk = FundListWalk::new();
+                    user_id += 1;
+                }
+                Err((violation, fund_id)) => {
+                    return self.accounting_violation(
+                        violation.reason(true),
+                        type_index,
+                        user_id,
+                        fund_id,
+                    );
+                }
+            }
+        }
+
+        let fund_type = FundType::ALL_TYPES[type_index];
+        if sum_user_totals != self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, fund_type) {
+            return self.accounting_violation(
+                b"user-type lists do not add up to the type list",
+                type_index,
+                0,
+                0,
+            );
+        }
+
+        if type_index + 1 < FundType::ALL_TYPES.len() {
+            AuditStepOutcome::Next(AccountingAuditStep::TypeList {
+                type_index: type_index + 1,
+                walk: FundListWalk::new(),
+            })
+        } else {
+            AuditStepOutcome::Next(AccountingAuditStep::UnclaimedRewards {
+                next_user_id: 1,
+                sum_unclaimed: BigUint::zero(),
+            })
+        }
+    }
+
+    fn audit_unclaimed_rewards(
+        &self,
+        mut next_user_id: usize,
+        mut sum_unclaimed: BigUint,
+    ) -> AuditStepOutcome<Self::Api> {
+        let num_users = self.get_num_users();
+        while next_user_id <= num_users {
+            if self.blockchain().get_gas_left() < AUDIT_GASLIMIT {
+                return AuditStepOutcome::Interrupted(AccountingAuditStep::UnclaimedRewards {
+                    next_user_id,
+                    sum_unclaimed,
+                });
+            }
+
+            if let Some(user_id) = NonZeroUsize::new(next_user_id) {
+                sum_unclaimed += self.load_updated_user_rewards(user_id).unclaimed_rewards;
+            }
+            next_user_id += 1;
+        }
+
+        let mut owed = sum_unclaimed;
+        owed += self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Waiting);
+        owed += self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::DeferredPayment);
+        owed += self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::WithdrawOnly);
+        let balance = self
+            .blockchain()
+            .get_sc_balance(EgldOrEsdtTokenIdentifier::egld(), 0);
+        if balance < owed {
+            return self.accounting_violation(
+                b"balance does not cover unclaimed rewards and funds in contract",
+                0,
+                0,
+                0,
+            );
+        }
+
+        AuditStepOutcome::Finished(AccountingAuditResult::Passed {
+            block_nonce: self.blockchain().get_block_nonce(),
+        })
+    }
+
+    /// Traverses a type list, or a user-type list, checking the links and adding up the balances.
+    /// Returns false if interrupted before the end of the list.
+    /// Returns the violation and the offending fund id if the list is inconsistent.
+    fn walk_fund_list(
+        &self,
+        walk: &mut FundListWalk<Self::Api>,
+        type_index: usize,
+        opt_user_id: Option<usize>,
+    ) -> Result<bool, (FundListViolation, usize)> {
+        if self.blockchain().get_gas_left() < AUDIT_GASLIMIT {
+            return Ok(false);
+        }
+
+        let fund_type = FundType::ALL_TYPES[type_index];
+        let list = if let Some(user_id) = opt_user_id {
+            self.fund_list_by_user(user_id, fund_type).get()
+        } else {
+            self.get_fund_list_by_type(fund_type)
+        };
+        if walk.current_id == 0 {
+            walk.current_id = list.first;
+        }
+
+        while walk.current_id > 0 {
+            if self.blockchain().get_gas_left() < AUDIT_GASLIMIT {
+                return Ok(false);
+            }
+
+            let id = walk.current_id;
+            let fund_item = self.fund_by_id(id).get();
+            if fund_item.fund_desc.fund_type() != fund_type {
+                return Err((FundListViolation::WrongFundType, id));
+            }
+
+            let (prev_id, next_id) = if let Some(user_id) = opt_user_id {
+                if fund_item.user_id != user_id {
+                    return Err((FundListViolation::WrongUserId, id));
+                }
+                (fund_item.user_list_prev, fund_item.user_list_next)
+            } else {
+                (fund_item.type_list_prev, fund_item.type_list_next)
+            };
+            if prev_id != walk.prev_id {
+                return Err((FundListViolation::BrokenPrev, id));
+            }
+
+            walk.sum += &fund_item.balance;
+            walk.prev_id = id;
+            walk.current_id = next_id;
+        }
+
+        if list.last != walk.prev_id {
+            return Err((FundListViolation::BrokenLast, walk.prev_id));
+        }
+        if walk.sum != list.total_balance {
+            return Err((FundListViolation::SumMismatch, 0));
+        }
+
+        Ok(true)
+    }
+
+    fn accounting_violation(
+        &self,
+        reason: &[u8],
+        type_index: usize,
+        user_id: usize,
+        fund_id: usize,
+    ) -> AuditStepOutcome<Self::Api> {
+        AuditStepOutcome::Finished(AccountingAuditResult::Violation(AccountingViolation {
+            reason: ManagedBuffer::from(reason),
+            type_index,
+            user_id,
+            fund_id,
+            block_nonce: self.blockchain().get_block_nonce(),
+        }))
+    }
+}
@@ -0,0 +1,90 @@
+use multiversx_sc::{
+    api::ManagedTypeApi,
+    types::{BigUint, ManagedBuffer, ManagedVec},
+};
+
+multiversx_sc::derive_imports!();
+
+/// Models the interrupted state of the accounting audit.
+/// The fund max id, type list totals and rewards are the ones from when the audit started.
+/// Any change to the funds or rewards changes at least one of them, and the audit starts over.
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub struct AccountingAuditData<M: ManagedTypeApi> {
+    pub fund_max_id: usize,
+    pub type_totals: ManagedVec<M, BigUint<M>>,
+    pub total_cumulated_rewards: BigUint<M>,
+    pub sent_rewards: BigUint<M>,
+    pub step: AccountingAuditStep<M>,
+}
+
+/// Models the steps of the accounting audit. Types are referred to by their index in `FundType::ALL_TYPES`.
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub enum AccountingAuditStep<M: ManagedTypeApi> {
+    /// Walks the list of a type.
+    TypeList {
+        type_index: usize,
+        walk: FundListWalk<M>,
+    },
+
+    /// Walks the lists of all users for a type, adding up their totals.
+    UserLists {
+        type_index: usize,
+        user_id: usize,
+        walk: FundListWalk<M>,
+        sum_user_totals: BigUint<M>,
+    },
+
+    /// Adds up the unclaimed rewards of all users, then checks the EGLD balance.
+    UnclaimedRewards {
+        next_user_id: usize,
+        sum_unclaimed: BigUint<M>,
+    },
+}
+
+/// Models the interrupted traversal of a fund list.
+/// `current_id` = 0 means that the traversal has not started.
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub struct FundListWalk<M: ManagedTypeApi> {
+    pub current_id: usize,
+    pub prev_id: usize,
+    pub sum: BigUint<M>,
+}
+
+impl<M: ManagedTypeApi> FundListWalk<M> {
+    pub fn new() -> Self {
+        FundListWalk {
+            current_id: 0,
+            prev_id: 0,
+            sum: BigUint::zero(),
+        }
+    }
+}
+
+impl<M: ManagedTypeApi> Default for FundListWalk<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub enum AccountingAuditResult<M: ManagedTypeApi> {
+    NotRun,
+    Passed { block_nonce: u64 },
+    Violation(AccountingViolation<M>),
+}
+
+/// The first inconsistency found by the audit.
+/// The fund id and user id are 0 when not relevant.
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub struct AccountingViolation<M: ManagedTypeApi> {
+    pub reason: ManagedBuffer<M>,
+    pub type_index: usize,
+    pub user_id: usize,
+    pub fund_id: usize,
+    pub block_nonce: u64,
+}
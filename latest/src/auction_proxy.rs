@@ -63,6 +63,11 @@ where
             .original_result()
     }
 
+    /// `stake` without arguments adds the payment to the top-up of the already staked nodes.
+    pub fn stake_top_up(self) -> TxProxyCall<Env, From, To, Gas, ()> {
+        self.wrapped_tx.raw_call("stake").original_result()
+    }
+
     pub fn unstake<
         Arg0: ProxyArg<MultiValueManagedVec<Env::Api, node_storage::types::BLSKey<Env::Api>>>,
     >(
@@ -146,4 +151,30 @@ where
             .argument(&bls_keys)
             .original_result()
     }
+
+    pub fn get_bls_keys_status<Arg0: ProxyArg<ManagedAddress<Env::Api>>>(
+        self,
+        owner: Arg0,
+    ) -> TxProxyCall<
+        Env,
+        From,
+        To,
+        Gas,
+        MultiValueEncoded<Env::Api, MultiValue2<ManagedBuffer<Env::Api>, ManagedBuffer<Env::Api>>>,
+    > {
+        self.wrapped_tx
+            .raw_call("getBlsKeysStatus")
+            .argument(&owner)
+            .original_result()
+    }
+
+    pub fn get_total_staked_top_up_staked_bls_keys<Arg0: ProxyArg<ManagedAddress<Env::Api>>>(
+        self,
+        owner: Arg0,
+    ) -> TxProxyCall<Env, From, To, Gas, MultiValueEncoded<Env::Api, ManagedBuffer<Env::Api>>> {
+        self.wrapped_tx
+            .raw_call("getTotalStakedTopUpStakedBlsKeys")
+            .argument(&owner)
+            .original_result()
+    }
 }
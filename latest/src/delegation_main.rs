@@ -6,15 +6,25 @@
 pub mod auction_proxy;
 
 // modules
+pub mod accounting_audit;
+pub mod accounting_audit_types;
+pub mod emergency_mode;
+pub mod emergency_mode_state;
+pub mod escape_hatch;
 pub mod events;
 pub mod governance;
 pub mod node_activation;
+pub mod node_jail_history;
 pub mod reset_checkpoint_endpoints;
 pub mod reset_checkpoint_state;
 pub mod reset_checkpoint_types;
 pub mod rewards_endpoints;
 pub mod rewards_state;
 pub mod settings;
+pub mod simulation;
+pub mod sunset;
+pub mod sunset_state;
+pub mod sunset_types;
 pub mod user_stake_dust_cleanup;
 pub mod user_stake_endpoints;
 pub mod user_stake_state;
@@ -37,6 +47,7 @@ pub trait DelegationFull:
     + user_fund_storage::fund_view_module::FundViewModule
     + user_fund_storage::fund_transf_module::FundTransformationsModule
     + node_activation::NodeActivationModule
+    + node_jail_history::NodeJailHistoryModule
     + settings::SettingsModule
     + reset_checkpoint_state::ResetCheckpointStateModule
     + rewards_state::RewardStateModule
@@ -46,6 +57,13 @@ pub trait DelegationFull:
     + rewards_endpoints::RewardEndpointsModule
     + user_stake_endpoints::UserStakeEndpointsModule
     + user_stake_dust_cleanup::UserStakeDustCleanupModule
+    + accounting_audit::AccountingAuditModule
+    + emergency_mode_state::EmergencyModeStateModule
+    + emergency_mode::EmergencyModeModule
+    + simulation::SimulationModule
+    + sunset_state::SunsetStateModule
+    + sunset::SunsetModule
+    + escape_hatch::EscapeHatchModule
     + multiversx_sc_modules::dns::DnsModule
     + multiversx_sc_modules::features::FeaturesModule
     + multiversx_sc_modules::pause::PauseModule
@@ -71,6 +89,7 @@ pub trait DelegationFull:
         n_blocks_before_unbond: u64,
         minimum_stake: BigUint,
         total_delegation_cap: BigUint,
+        opt_bootstrap_deadline: OptionalValue<u64>,
     ) {
         let owner = self.blockchain().get_caller();
         self.set_user_id(&owner, OWNER_USER_ID.get()); // node reward destination will be user #1
@@ -94,6 +113,10 @@ pub trait DelegationFull:
 
         self.set_total_delegation_cap(total_delegation_cap);
         self.set_bootstrap_mode(true);
+        if let OptionalValue::Some(bootstrap_deadline) = opt_bootstrap_deadline {
+            self.bootstrap_deadline().set(bootstrap_deadline);
+        }
+        self.node_state_indexes_built().set(true);
     }
 
     /// the genesis contract didn't have the concept of total delegation cap
@@ -115,5 +138,6 @@ pub trait DelegationFull:
     #[label("upgrade")]
     fn upgrade(&self) {
         self.update_total_delegation_cap_if_necessary();
+        self.build_node_state_indexes_if_necessary();
     }
 }
@@ -0,0 +1,76 @@
+multiversx_sc::imports!();
+
+/// Lets anyone freeze the contract when the books are wrong, rather than have funds move.
+///
+/// Emergency mode stops staking, node operations and cap changes, like a pause.
+/// Unlike a regular pause, `unBond` and `claimRewards` stay available, so delegators can still leave.
+/// Only `exitEmergencyMode` lifts it, once the invariants hold again.
+#[multiversx_sc::derive::module]
+pub trait EmergencyModeModule:
+    crate::user_stake_state::UserStakeStateModule
+    + crate::rewards_state::RewardStateModule
+    + crate::reset_checkpoint_state::ResetCheckpointStateModule
+    + crate::settings::SettingsModule
+    + crate::events::EventsModule
+    + user_fund_storage::user_data::UserDataModule
+    + user_fund_storage::fund_module::FundModule
+    + user_fund_storage::fund_view_module::FundViewModule
+    + user_fund_storage::fund_transf_module::FundTransformationsModule
+    + multiversx_sc_modules::pause::PauseModule
+    + crate::emergency_mode_state::EmergencyModeStateModule
+{
+    /// The invariant found violated when emergency mode was triggered.
+    #[view(getEmergencyModeReason)]
+    #[storage_mapper("emergency_mode_reason")]
+    fn emergency_mode_reason(&self) -> SingleValueMapper<ManagedBuffer>;
+
+    /// The first invariant found violated, if any.
+    /// Only true invariants count, since anyone can trigger emergency mode.
+    /// The owner stake share, for instance, drops below the minimum in normal operation, whenever delegators stake.
+    /// The delegation cap invariant only holds once global operations complete.
+    fn find_invariant_violation(&self) -> Option<&'static [u8]> {
+        if !self.is_global_op_in_progress() && !self.is_delegation_cap_invariant_valid() {
+            return Some(b"delegation cap invariant violated");
+        }
+        if self.try_total_unprotected().is_none() {
+            return Some(b"total unprotected underflow");
+        }
+
+        None
+    }
+
+    /// Can be called by anyone. Only succeeds if one of the invariants is currently violated.
+    #[endpoint(triggerEmergencyMode)]
+    fn trigger_emergency_mode(&self) {
+        require!(
+            !self.emergency_mode().get(),
+            "emergency mode already active"
+        );
+
+        let reason = self
+            .find_invariant_violation()
+            .unwrap_or_else(|| sc_panic!("no invariant violated"));
+        let reason = ManagedBuffer::from(reason);
+
+        self.emergency_mode().set(true);
+        self.emergency_mode_reason().set(&reason);
+
+        self.emergency_mode_triggered_event(&self.blockchain().get_caller(), &reason);
+    }
+
+    /// Lifts emergency mode, once the invariants hold again.
+    #[only_owner]
+    #[endpoint(exitEmergencyMode)]
+    fn exit_emergency_mode(&self) {
+        require!(self.emergency_mode().get(), "emergency mode not active");
+        require!(
+            self.find_invariant_violation().is_none(),
+            "invariants still violated"
+        );
+
+        self.emergency_mode().clear();
+        self.emergency_mode_reason().clear();
+
+        self.emergency_mode_exited_event();
+    }
+}
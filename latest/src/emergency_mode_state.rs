@@ -0,0 +1,27 @@
+multiversx_sc::imports!();
+
+/// The emergency mode flag, and the guards that depend on it.
+///
+/// The flag is kept apart from the pause flag of the `PauseModule`, so that `unpause` cannot lift emergency mode.
+#[multiversx_sc::derive::module]
+pub trait EmergencyModeStateModule: multiversx_sc_modules::pause::PauseModule {
+    #[view(isEmergencyMode)]
+    #[storage_mapper("emergency_mode")]
+    fn emergency_mode(&self) -> SingleValueMapper<bool>;
+
+    /// Staking, node operations and cap changes stop when the owner pauses the contract, and in emergency mode.
+    fn require_operational(&self) {
+        require!(
+            self.not_paused() && !self.emergency_mode().get(),
+            "contract paused"
+        );
+    }
+
+    /// Endpoints that let delegators take their funds out remain available in emergency mode.
+    fn require_not_paused_or_emergency(&self) {
+        require!(
+            self.not_paused() || self.emergency_mode().get(),
+            "contract paused"
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use node_storage::{node_config::MAX_NODES_PER_OPERATION, types::NodeState};
+use user_fund_storage::fund_module::SwapDirection;
+use user_fund_storage::fund_view_module::USER_STAKE_TOTALS_ID;
+use user_fund_storage::types::FundType;
+
+multiversx_sc::imports!();
+
+/// Lets delegators get their stake out when the owner stops maintaining the nodes.
+///
+/// UnStaked stake normally waits until Waiting stake replaces it or the owner lowers the cap.
+/// If the oldest UnStaked stake waited for longer than the configured number of epochs,
+/// anyone can unstake and unbond nodes, then lower the cap, so that the UnStaked stake becomes deferred payment.
+#[multiversx_sc::derive::module]
+pub trait EscapeHatchModule:
+    node_storage::node_config::NodeConfigModule
+    + user_fund_storage::user_data::UserDataModule
+    + user_fund_storage::fund_module::FundModule
+    + user_fund_storage::fund_view_module::FundViewModule
+    + user_fund_storage::fund_transf_module::FundTransformationsModule
+    + crate::settings::SettingsModule
+    + crate::reset_checkpoint_state::ResetCheckpointStateModule
+    + crate::reset_checkpoint_endpoints::ResetCheckpointsModule
+    + crate::rewards_state::RewardStateModule
+    + crate::user_stake_state::UserStakeStateModule
+    + crate::events::EventsModule
+    + crate::node_jail_history::NodeJailHistoryModule
+    + crate::node_activation::NodeActivationModule
+    + crate::sunset_state::SunsetStateModule
+    + multiversx_sc_modules::features::FeaturesModule
+    + multiversx_sc_modules::pause::PauseModule
+    + crate::emergency_mode_state::EmergencyModeStateModule
+{
+    /// Number of epochs UnStaked stake can wait before anyone can use the escape hatch.
+    /// 0 disables the escape hatch.
+    /// While UnStaked stake is waiting, the delay can only be shortened.
+    #[only_owner]
+    #[endpoint(setEscapeHatchEpochs)]
+    fn set_escape_hatch_epochs(&self, epochs: u64) {
+        let current_epochs = self.escape_hatch_epochs().get();
+        if self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::UnStaked) > 0 {
+            require!(
+                epochs > 0 && (current_epochs == 0 || epochs <= current_epochs),
+                "cannot delay the escape hatch while unstaked stake is waiting"
+            );
+        }
+
+        if current_epochs == 0 {
+            self.escape_hatch_enabled_epoch()
+                .set(self.blockchain().get_block_epoch());
+        }
+        self.escape_hatch_epochs().set(epochs);
+    }
+
+    #[view(getEscapeHatchEpochs)]
+    #[storage_mapper("escape_hatch_epochs")]
+    fn escape_hatch_epochs(&self) -> SingleValueMapper<u64>;
+
+    /// UnStaked stake only counts as waiting from the epoch the escape hatch was enabled.
+    #[storage_mapper("escape_hatch_enabled_epoch")]
+    fn escape_hatch_enabled_epoch(&self) -> SingleValueMapper<u64>;
+
+    /// The epoch from which the escape hatch can be used, based on the oldest UnStaked stake.
+    /// Yields nothing if the escape hatch is disabled or there is no UnStaked stake.
+    #[view(getEscapeHatchEpoch)]
+    fn get_escape_hatch_epoch(&self) -> OptionalValue<u64> {
+        let epochs = self.escape_hatch_epochs().get();
+        let oldest_id = self.first_id_of_type(FundType::UnStaked, SwapDirection::Forwards);
+        if epochs == 0 || oldest_id == 0 {
+            return OptionalValue::None;
+        }
+
+        let waiting_since = core::cmp::max(
+            self.unstaked_fund_epoch(oldest_id).get(),
+            self.escape_hatch_enabled_epoch().get(),
+        );
+        OptionalValue::Some(waiting_since + epochs)
+    }
+
+    /// Can be called by anyone, once the oldest UnStaked stake waited for too long, even while the contract is paused.
+    /// Each call performs the next step:
+    /// - if the contract has enough free funds, lowers the cap by the UnStaked amount,
+    ///   which converts all UnStaked stake to deferred payment, as a global operation;
+    /// - otherwise, if nodes are in the unbond period, unbonds them;
+    ///   the auction SC rejects the nodes whose unbond period did not pass yet;
+    /// - otherwise, unstakes as many Active nodes as needed to cover the UnStaked amount, together with their tokens,
+    ///   counting the nodes already being unstaked or unbonded.
+    #[endpoint(escapeHatch)]
+    fn escape_hatch(&self) -> OperationCompletionStatus {
+        self.require_not_sunset();
+        require!(
+            !self.is_global_op_in_progress(),
+            "global checkpoint is in progress"
+        );
+
+        let escape_hatch_epoch = match self.get_escape_hatch_epoch() {
+            OptionalValue::Some(epoch) => epoch,
+            OptionalValue::None => sc_panic!("no unstaked stake waiting"),
+        };
+        require!(
+            self.blockchain().get_block_epoch() >= escape_hatch_epoch,
+            "unstaked stake has not waited long enough"
+        );
+
+        let total_unstaked = self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::UnStaked);
+        self.escape_hatch_event(&self.blockchain().get_caller(), &total_unstaked);
+
+        let total_unprotected = self.total_unprotected();
+        if total_unprotected >= total_unstaked {
+            let new_total_cap = self.get_total_delegation_cap() - &total_unstaked;
+            return self.start_total_delegation_cap_change(new_total_cap);
+        }
+
+        // nodes stuck in a pending state are not waited for, only the owner can recover them
+        let unbond_period_node_ids = self.first_nodes_in_state(
+            NodeState::UnBondPeriod { started: 0 },
+            MAX_NODES_PER_OPERATION,
+        );
+        if !unbond_period_node_ids.is_empty() {
+            let bls_keys = self.bls_keys_of_nodes(&unbond_period_node_ids);
+            for &node_id in unbond_period_node_ids.iter() {
+                self.prepare_node_for_unbond_if_possible(node_id);
+            }
+            self.perform_unbond(unbond_period_node_ids, bls_keys);
+        }
+
+        // the stake of the nodes is estimated by spreading the cap evenly over the Active nodes
+        // and the nodes already being unstaked or unbonded, whose stake is not back yet
+        let num_active_nodes = self.nodes_by_state(NodeState::Active.discriminant()).len();
+        let num_leaving_nodes = self.num_leaving_nodes();
+        require!(num_active_nodes > 0, "no active nodes to unstake");
+        let stake_per_node =
+            self.get_total_delegation_cap() / (num_active_nodes + num_leaving_nodes) as u64;
+        require!(stake_per_node > 0, "no active nodes to unstake");
+        let leaving_stake = &stake_per_node * num_leaving_nodes as u64;
+        require!(
+            total_unstaked > &total_unprotected + &leaving_stake,
+            "the nodes already leaving cover the unstaked stake"
+        );
+        let missing = &total_unstaked - &total_unprotected - &leaving_stake;
+        let num_nodes_needed = ((&missing + &stake_per_node - 1u32) / &stake_per_node)
+            .to_u64()
+            .map_or(MAX_NODES_PER_OPERATION, |n| n as usize);
+
+        let node_ids = self.first_nodes_in_state(NodeState::Active, num_nodes_needed);
+        let bls_keys = self.bls_keys_of_nodes(&node_ids);
+        self.perform_unstake_nodes(true, node_ids, bls_keys);
+        OperationCompletionStatus::Completed
+    }
+
+    /// Nodes whose unstake or unbond was sent to the auction SC, but whose stake is not back yet.
+    fn num_leaving_nodes(&self) -> usize {
+        [
+            NodeState::PendingDeactivation,
+            NodeState::UnBondPeriod { started: 0 },
+            NodeState::PendingUnBond { unbond_started: 0 },
+        ]
+        .iter()
+        .map(|state| self.nodes_by_state(state.discriminant()).len())
+        .sum()
+    }
+}
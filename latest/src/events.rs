@@ -1,3 +1,6 @@
+use crate::sunset_types::SunsetPhase;
+use node_storage::types::BLSKey;
+
 multiversx_sc::imports!();
 
 /// Contains all events that can be emitted by the contract.
@@ -15,6 +18,24 @@ pub trait EventsModule {
     #[event("nodeStakeFail")]
     fn stake_node_fail_event(&self, reason: &ManagedBuffer);
 
+    #[event("topUpStakeOk")]
+    fn top_up_stake_ok_event(&self, amount: &BigUint);
+
+    #[event("topUpStakeFail")]
+    fn top_up_stake_fail_event(&self, reason: &ManagedBuffer);
+
+    #[event("topUpUnstakeOk")]
+    fn top_up_unstake_ok_event(&self, amount: &BigUint);
+
+    #[event("topUpUnstakeFail")]
+    fn top_up_unstake_fail_event(&self, reason: &ManagedBuffer);
+
+    #[event("topUpUnbondOk")]
+    fn top_up_unbond_ok_event(&self, amount: &BigUint);
+
+    #[event("topUpUnbondFail")]
+    fn top_up_unbond_fail_event(&self, reason: &ManagedBuffer);
+
     #[event("nodeUnstakeOk")]
     fn unstake_node_ok_event(&self);
 
@@ -27,6 +48,42 @@ pub trait EventsModule {
     #[event("nodeUnbondFail")]
     fn unbond_node_fail_event(&self, reason: &ManagedBuffer);
 
+    #[event("stuckNodeRecovered")]
+    fn stuck_node_recovered_event(
+        &self,
+        #[indexed] bls_key: &BLSKey<Self::Api>,
+        #[indexed] pending_state: u8,
+        #[indexed] new_state: u8,
+    );
+
+    #[event("nodeStateCorrected")]
+    fn node_state_corrected_event(
+        &self,
+        #[indexed] bls_key: &BLSKey<Self::Api>,
+        #[indexed] old_state: u8,
+        #[indexed] new_state: u8,
+    );
+
+    #[event("auctionQueryFail")]
+    fn auction_query_fail_event(&self, reason: &ManagedBuffer);
+
+    #[event("auctionStakeReported")]
+    fn auction_stake_reported_event(
+        &self,
+        #[indexed] top_up: &BigUint,
+        #[indexed] total_staked: &BigUint,
+        #[indexed] num_staked_nodes: u64,
+    );
+
+    #[event("nodeJailed")]
+    fn node_jailed_event(&self, #[indexed] bls_key: &BLSKey<Self::Api>);
+
+    #[event("nodeUnjailed")]
+    fn node_unjailed_event(&self, #[indexed] bls_key: &BLSKey<Self::Api>, fine: &BigUint);
+
+    #[event("nodeUnjailFail")]
+    fn unjail_node_fail_event(&self, reason: &ManagedBuffer);
+
     #[event("tokensUnstake")]
     fn unstake_tokens_event(&self, amount: &BigUint);
 
@@ -35,4 +92,27 @@ pub trait EventsModule {
 
     #[event("userClaimRewards")]
     fn claim_rewards_event(&self, #[indexed] user: &ManagedAddress, amount: &BigUint);
+
+    #[event("emergencyModeTriggered")]
+    fn emergency_mode_triggered_event(
+        &self,
+        #[indexed] caller: &ManagedAddress,
+        reason: &ManagedBuffer,
+    );
+
+    #[event("emergencyModeExited")]
+    fn emergency_mode_exited_event(&self);
+
+    #[event("sunsetPhaseChanged")]
+    fn sunset_phase_changed_event(&self, #[indexed] phase: SunsetPhase);
+
+    #[event("escapeHatch")]
+    fn escape_hatch_event(&self, #[indexed] caller: &ManagedAddress, total_unstaked: &BigUint);
+
+    #[event("bootstrapStakeReclaimed")]
+    fn bootstrap_stake_reclaimed_event(
+        &self,
+        #[indexed] delegator: &ManagedAddress,
+        amount: &BigUint,
+    );
 }
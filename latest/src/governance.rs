@@ -1,6 +1,44 @@
+use multiversx_sc::derive_imports::*;
 use multiversx_sc::imports::*;
+use user_fund_storage::fund_view_module::USER_STAKE_TOTALS_ID;
 use user_fund_storage::types::FundType;
 
+/// Keeps the representative votes within the gas limit of a transaction.
+pub const MAX_REPRESENTED_USERS: usize = 50;
+
+/// A vote cast through the contract, as accepted by the governance system SC.
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone, Debug)]
+pub struct UserVote<M: ManagedTypeApi> {
+    pub vote: ManagedBuffer<M>,
+    pub voting_power: BigUint<M>,
+}
+
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone, Copy, Debug)]
+pub enum PoolVoteStatus {
+    /// Delegators can still vote themselves, until the deadline.
+    Scheduled,
+
+    /// Forwarded to the governance system SC, waiting for the result.
+    Pending,
+
+    /// Accepted by the governance system SC.
+    Cast,
+}
+
+/// The vote cast on behalf of all delegators who did not vote themselves.
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone, Debug)]
+pub struct PoolVote<M: ManagedTypeApi> {
+    pub vote: ManagedBuffer<M>,
+    pub deadline_nonce: u64,
+    pub status: PoolVoteStatus,
+
+    /// Zero until the pool vote is forwarded.
+    pub voting_power: BigUint<M>,
+}
+
 /// Contains logic to forward governance votes to the governance system SC.
 #[multiversx_sc::derive::module]
 pub trait GovernanceModule:
@@ -8,16 +46,63 @@ pub trait GovernanceModule:
     + user_fund_storage::fund_module::FundModule
     + user_fund_storage::fund_view_module::FundViewModule
 {
+    /// Forwards the vote of the caller to the governance system SC, weighted by their active stake.
+    /// Each user can vote only once per proposal.
+    /// The vote is recorded only after the governance system SC accepts it.
+    ///
+    /// The voting power is snapshotted when voting, and that much Active stake stays locked
+    /// until voting on the proposal ends, so the same stake cannot be moved to another address and vote again.
+    /// Only proposals registered with `registerProposal` can be voted on, so that the end of the lock is known.
+    ///
+    /// Representatives also vote for all the users who assigned them their voting power,
+    /// unless these already voted on the proposal.
     #[endpoint(delegateVote)]
     fn delegate_vote(&self, proposal_to_vote: u64, vote: ManagedBuffer) {
         let voter = self.blockchain().get_caller();
 
-        let staked_balance = self.get_user_stake_of_type_by_address(&voter, FundType::Active);
+        require!(
+            !self.proposal_end_nonce(proposal_to_vote).is_empty(),
+            "proposal is not registered"
+        );
+        require!(
+            self.is_proposal_voting_open(proposal_to_vote),
+            "voting on this proposal has ended"
+        );
+        require!(
+            self.user_vote(proposal_to_vote, &voter).is_empty(),
+            "already voted on this proposal"
+        );
+        require!(
+            !self.pending_vote(proposal_to_vote, &voter).get(),
+            "a vote on this proposal is already pending"
+        );
+        require!(
+            self.is_user_vote_allowed_by_pool_vote(proposal_to_vote),
+            "the pool vote deadline for this proposal has passed"
+        );
+        require!(
+            self.representative(&voter).is_empty(),
+            "voting power is assigned to a representative"
+        );
+
+        let mut voting_power = self.start_user_vote(proposal_to_vote, &voter);
+
+        let mut represented_voters = ManagedVec::<Self::Api, ManagedAddress>::new();
+        for user in self.represented_users(&voter).iter() {
+            if self.can_user_vote(proposal_to_vote, &user) {
+                voting_power += self.start_user_vote(proposal_to_vote, &user);
+                represented_voters.push(user);
+            }
+        }
+        if !represented_voters.is_empty() {
+            self.represented_voters(proposal_to_vote, &voter)
+                .set(&represented_voters);
+        }
 
         self.tx()
             .to(GovernanceSystemSCAddress)
             .typed(GovernanceSCProxy)
-            .delegate_vote(proposal_to_vote, &vote, &voter, &staked_balance)
+            .delegate_vote(proposal_to_vote, &vote, &voter, &voting_power)
             .callback(
                 self.callbacks()
                     .delegate_vote_callback(&voter, proposal_to_vote, &vote),
@@ -33,6 +118,13 @@ pub trait GovernanceModule:
         vote: &ManagedBuffer,
         #[call_result] call_result: ManagedAsyncCallResult<()>,
     ) {
+        let represented_voters = self.represented_voters(proposal_to_vote, voter).take();
+        let success = matches!(call_result, ManagedAsyncCallResult::Ok(()));
+        self.finish_user_vote(proposal_to_vote, voter, vote, success);
+        for user in represented_voters.iter() {
+            self.finish_user_vote(proposal_to_vote, &user, vote, success);
+        }
+
         match call_result {
             ManagedAsyncCallResult::Ok(()) => {
                 self.delegate_vote_success_event(voter, proposal_to_vote, vote);
@@ -43,6 +135,256 @@ pub trait GovernanceModule:
         }
     }
 
+    /// Whether the user has neither voted on the proposal, nor has a vote on it pending.
+    fn can_user_vote(&self, proposal: u64, user: &ManagedAddress) -> bool {
+        self.user_vote(proposal, user).is_empty() && !self.pending_vote(proposal, user).get()
+    }
+
+    /// Snapshots and locks the voting power of a user, while their vote is forwarded.
+    fn start_user_vote(&self, proposal: u64, voter: &ManagedAddress) -> BigUint {
+        let staked_balance = self.get_user_stake_of_type_by_address(voter, FundType::Active);
+
+        self.release_expired_vote_locks(voter);
+        self.pending_vote(proposal, voter).set(true);
+        self.user_vote_locks(voter).insert(proposal);
+        self.locked_voting_power(proposal, voter)
+            .set(&staked_balance);
+        self.proposal_user_voted_power(proposal)
+            .update(|power| *power += &staked_balance);
+
+        staked_balance
+    }
+
+    /// Records the vote if the governance system SC accepted it, otherwise releases the voting power.
+    fn finish_user_vote(
+        &self,
+        proposal: u64,
+        voter: &ManagedAddress,
+        vote: &ManagedBuffer,
+        success: bool,
+    ) {
+        self.pending_vote(proposal, voter).clear();
+
+        let voting_power = self.locked_voting_power(proposal, voter).get();
+        if success {
+            self.record_vote(voter, proposal, vote, &voting_power);
+        } else {
+            self.user_vote_locks(voter).swap_remove(&proposal);
+            self.locked_voting_power(proposal, voter).clear();
+            self.proposal_user_voted_power(proposal)
+                .update(|power| *power -= &voting_power);
+        }
+    }
+
+    /// Assigns the voting power of the caller to a representative, who will vote on their behalf.
+    /// Representatives cannot assign their own voting power further,
+    /// and represent at most `MAX_REPRESENTED_USERS` users.
+    #[endpoint(assignRepresentative)]
+    fn assign_representative(&self, representative: ManagedAddress) {
+        let user = self.blockchain().get_caller();
+        require!(user != representative, "cannot represent yourself");
+        require!(
+            self.represented_users(&user).is_empty(),
+            "representatives cannot assign their voting power"
+        );
+        require!(
+            self.representative(&representative).is_empty(),
+            "the representative assigned their own voting power"
+        );
+
+        require!(
+            self.represented_users(&representative).len() < MAX_REPRESENTED_USERS,
+            "the representative cannot represent more users"
+        );
+
+        let representative_mapper = self.representative(&user);
+        if !representative_mapper.is_empty() {
+            let previous_representative = representative_mapper.get();
+            self.represented_users(&previous_representative)
+                .swap_remove(&user);
+            self.revoke_representative_event(&user, &previous_representative);
+        }
+
+        representative_mapper.set(&representative);
+        self.represented_users(&representative).insert(user.clone());
+        self.assign_representative_event(&user, &representative);
+    }
+
+    /// The caller takes back their voting power from their representative.
+    #[endpoint(revokeRepresentative)]
+    fn revoke_representative(&self) {
+        let user = self.blockchain().get_caller();
+        let representative_mapper = self.representative(&user);
+        require!(
+            !representative_mapper.is_empty(),
+            "no representative assigned"
+        );
+
+        let representative = representative_mapper.take();
+        self.represented_users(&representative).swap_remove(&user);
+        self.revoke_representative_event(&user, &representative);
+    }
+
+    /// Voting power a representative would vote with on a proposal: their own Active stake,
+    /// plus the Active stake of all users who assigned them their voting power and did not vote on it themselves.
+    #[view(getRepresentativeVotingPower)]
+    fn get_representative_voting_power(
+        &self,
+        proposal: u64,
+        representative: ManagedAddress,
+    ) -> BigUint {
+        let mut voting_power =
+            self.get_user_stake_of_type_by_address(&representative, FundType::Active);
+        for user in self.represented_users(&representative).iter() {
+            if self.can_user_vote(proposal, &user) {
+                voting_power += self.get_user_stake_of_type_by_address(&user, FundType::Active);
+            }
+        }
+        voting_power
+    }
+
+    fn record_vote(
+        &self,
+        voter: &ManagedAddress,
+        proposal: u64,
+        vote: &ManagedBuffer,
+        voting_power: &BigUint,
+    ) {
+        self.user_vote(proposal, voter).set(UserVote {
+            vote: vote.clone(),
+            voting_power: voting_power.clone(),
+        });
+        self.add_to_proposal_tally(proposal, vote, voting_power);
+    }
+
+    fn add_to_proposal_tally(&self, proposal: u64, vote: &ManagedBuffer, voting_power: &BigUint) {
+        self.proposal_vote_options(proposal).insert(vote.clone());
+        self.proposal_tally_by_option(proposal, vote)
+            .update(|tally| *tally += voting_power);
+    }
+
+    /// Schedules a vote on behalf of all delegators who do not vote themselves.
+    /// Delegators can still vote with `delegateVote` until the deadline nonce,
+    /// after which the pool vote can be cast with `castPoolVote`.
+    /// Can be changed as long as it was not cast.
+    #[only_owner]
+    #[endpoint(setPoolVote)]
+    fn set_pool_vote(&self, proposal: u64, vote: ManagedBuffer, deadline_nonce: u64) {
+        require!(
+            self.is_proposal_voting_open(proposal),
+            "voting on this proposal has ended"
+        );
+        require!(
+            deadline_nonce > self.blockchain().get_block_nonce(),
+            "pool vote deadline must be in the future"
+        );
+
+        let pool_vote_mapper = self.pool_vote(proposal);
+        require!(
+            pool_vote_mapper.is_empty()
+                || pool_vote_mapper.get().status == PoolVoteStatus::Scheduled,
+            "pool vote already cast"
+        );
+
+        pool_vote_mapper.set(PoolVote {
+            vote,
+            deadline_nonce,
+            status: PoolVoteStatus::Scheduled,
+            voting_power: BigUint::zero(),
+        });
+    }
+
+    /// Forwards the pool vote to the governance system SC, once the deadline has passed.
+    /// It is weighted by the total Active stake, minus the voting power of the delegators who voted themselves.
+    #[only_owner]
+    #[endpoint(castPoolVote)]
+    fn cast_pool_vote(&self, proposal: u64) {
+        require!(
+            self.is_proposal_voting_open(proposal),
+            "voting on this proposal has ended"
+        );
+
+        let pool_vote_mapper = self.pool_vote(proposal);
+        require!(
+            !pool_vote_mapper.is_empty(),
+            "no pool vote for this proposal"
+        );
+        let mut pool_vote = pool_vote_mapper.get();
+        require!(
+            pool_vote.status == PoolVoteStatus::Scheduled,
+            "pool vote already cast"
+        );
+        require!(
+            self.blockchain().get_block_nonce() >= pool_vote.deadline_nonce,
+            "pool vote deadline not reached"
+        );
+
+        let total_active = self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Active);
+        let user_voted_power = self.proposal_user_voted_power(proposal).get();
+        // users who voted might have lost Active stake since, so the difference can go below zero
+        pool_vote.voting_power = if total_active > user_voted_power {
+            total_active - user_voted_power
+        } else {
+            BigUint::zero()
+        };
+        require!(
+            pool_vote.voting_power > 0,
+            "no voting power left for the pool vote"
+        );
+
+        pool_vote.status = PoolVoteStatus::Pending;
+        pool_vote_mapper.set(&pool_vote);
+
+        let pool_address = self.blockchain().get_sc_address();
+        self.tx()
+            .to(GovernanceSystemSCAddress)
+            .typed(GovernanceSCProxy)
+            .delegate_vote(
+                proposal,
+                &pool_vote.vote,
+                &pool_address,
+                &pool_vote.voting_power,
+            )
+            .callback(self.callbacks().pool_vote_callback(proposal))
+            .async_call_and_exit();
+    }
+
+    #[callback]
+    fn pool_vote_callback(
+        &self,
+        proposal: u64,
+        #[call_result] call_result: ManagedAsyncCallResult<()>,
+    ) {
+        let pool_vote_mapper = self.pool_vote(proposal);
+        let mut pool_vote = pool_vote_mapper.get();
+        match call_result {
+            ManagedAsyncCallResult::Ok(()) => {
+                pool_vote.status = PoolVoteStatus::Cast;
+                self.add_to_proposal_tally(proposal, &pool_vote.vote, &pool_vote.voting_power);
+                self.pool_vote_success_event(proposal, &pool_vote.vote, &pool_vote.voting_power);
+            }
+            ManagedAsyncCallResult::Err(error) => {
+                // can be retried
+                pool_vote.status = PoolVoteStatus::Scheduled;
+                pool_vote.voting_power = BigUint::zero();
+                self.pool_vote_error_event(proposal, &pool_vote.vote, &error.err_msg);
+            }
+        }
+        pool_vote_mapper.set(&pool_vote);
+    }
+
+    /// Delegators can override the pool vote only until its deadline.
+    fn is_user_vote_allowed_by_pool_vote(&self, proposal: u64) -> bool {
+        let pool_vote_mapper = self.pool_vote(proposal);
+        if pool_vote_mapper.is_empty() {
+            return true;
+        }
+
+        let pool_vote = pool_vote_mapper.get();
+        pool_vote.status == PoolVoteStatus::Scheduled
+            && self.blockchain().get_block_nonce() < pool_vote.deadline_nonce
+    }
+
     #[event("delegateVoteSuccess")]
     fn delegate_vote_success_event(
         &self,
@@ -60,9 +402,205 @@ pub trait GovernanceModule:
         error_msg: &ManagedBuffer,
     );
 
+    #[event("assignRepresentative")]
+    fn assign_representative_event(
+        &self,
+        #[indexed] user: &ManagedAddress,
+        #[indexed] representative: &ManagedAddress,
+    );
+
+    #[event("revokeRepresentative")]
+    fn revoke_representative_event(
+        &self,
+        #[indexed] user: &ManagedAddress,
+        #[indexed] representative: &ManagedAddress,
+    );
+
+    #[event("poolVoteSuccess")]
+    fn pool_vote_success_event(
+        &self,
+        #[indexed] proposal: u64,
+        #[indexed] vote: &ManagedBuffer,
+        voting_power: &BigUint,
+    );
+
+    #[event("poolVoteError")]
+    fn pool_vote_error_event(
+        &self,
+        #[indexed] proposal: u64,
+        #[indexed] vote: &ManagedBuffer,
+        error_msg: &ManagedBuffer,
+    );
+
     /// Voting power of a single user, based on their active stake.
     #[view(getVotingPower)]
     fn get_voting_power(&self, voter: ManagedAddress) -> BigUint {
         self.get_user_stake_of_type_by_address(&voter, FundType::Active)
     }
+
+    /// Registers the block nonce at which voting on a proposal ends.
+    /// Delegators can only vote on registered proposals, and their votes lock the voting power until then.
+    /// Once registered, the end nonce can only be brought forward.
+    #[only_owner]
+    #[endpoint(registerProposal)]
+    fn register_proposal(&self, proposal: u64, end_nonce: u64) {
+        require!(end_nonce > 0, "invalid proposal end nonce");
+
+        let end_nonce_mapper = self.proposal_end_nonce(proposal);
+        let current_end_nonce = end_nonce_mapper.get();
+        require!(
+            current_end_nonce == 0 || end_nonce < current_end_nonce,
+            "proposal end nonce can only be brought forward"
+        );
+        end_nonce_mapper.set(end_nonce);
+    }
+
+    fn is_proposal_voting_open(&self, proposal: u64) -> bool {
+        let end_nonce = self.proposal_end_nonce(proposal).get();
+        end_nonce == 0 || self.blockchain().get_block_nonce() < end_nonce
+    }
+
+    fn release_expired_vote_locks(&self, voter: &ManagedAddress) {
+        let mut user_vote_locks = self.user_vote_locks(voter);
+        let expired: ManagedVec<u64> = user_vote_locks
+            .iter()
+            .filter(|proposal| !self.is_proposal_voting_open(*proposal))
+            .collect();
+        for proposal in expired.iter() {
+            user_vote_locks.swap_remove(&proposal);
+            self.locked_voting_power(proposal, voter).clear();
+        }
+    }
+
+    /// Active stake of a user that cannot be unstaked, because it voted on proposals still open.
+    /// The same stake can vote on several proposals, so the largest lock applies.
+    #[view(getLockedVotingPower)]
+    fn get_locked_voting_power(&self, voter: ManagedAddress) -> BigUint {
+        let mut locked = BigUint::zero();
+        for proposal in self.user_vote_locks(&voter).iter() {
+            if self.is_proposal_voting_open(proposal) {
+                let proposal_locked = self.locked_voting_power(proposal, &voter).get();
+                if proposal_locked > locked {
+                    locked = proposal_locked;
+                }
+            }
+        }
+        locked
+    }
+
+    /// Unstaking takes Waiting stake first, then Active stake.
+    /// The Active stake left after unstaking must still cover the locked voting power.
+    fn require_unstake_keeps_locked_voting_power(&self, voter: &ManagedAddress, amount: &BigUint) {
+        let locked = self.get_locked_voting_power(voter.clone());
+        if locked == 0 {
+            return;
+        }
+
+        let waiting = self.get_user_stake_of_type_by_address(voter, FundType::Waiting);
+        let active = self.get_user_stake_of_type_by_address(voter, FundType::Active);
+        let unlocked_active = if active > locked {
+            &active - &locked
+        } else {
+            BigUint::zero()
+        };
+        require!(
+            *amount <= waiting + unlocked_active,
+            "cannot unstake voting power locked by an open proposal"
+        );
+    }
+
+    /// Yields the vote of a user on a proposal, together with the voting power it was cast with.
+    /// Yields nothing if the user did not vote on the proposal through this contract.
+    #[view(getUserVote)]
+    fn get_user_vote(
+        &self,
+        proposal: u64,
+        voter: ManagedAddress,
+    ) -> OptionalValue<MultiValue2<ManagedBuffer, BigUint>> {
+        let user_vote_mapper = self.user_vote(proposal, &voter);
+        if user_vote_mapper.is_empty() {
+            return OptionalValue::None;
+        }
+
+        let user_vote = user_vote_mapper.get();
+        OptionalValue::Some((user_vote.vote, user_vote.voting_power).into())
+    }
+
+    /// Yields the voting power cast through this contract on a proposal, summed per vote option.
+    #[view(getProposalTally)]
+    fn get_proposal_tally(
+        &self,
+        proposal: u64,
+    ) -> MultiValueEncoded<MultiValue2<ManagedBuffer, BigUint>> {
+        let mut result = MultiValueEncoded::new();
+        for vote in self.proposal_vote_options(proposal).iter() {
+            let tally = self.proposal_tally_by_option(proposal, &vote).get();
+            result.push((vote, tally).into());
+        }
+        result
+    }
+
+    #[storage_mapper("user_vote")]
+    fn user_vote(
+        &self,
+        proposal: u64,
+        voter: &ManagedAddress,
+    ) -> SingleValueMapper<UserVote<Self::Api>>;
+
+    /// Set while the vote is forwarded to the governance system SC, to prevent voting twice in the meantime.
+    #[storage_mapper("pending_vote")]
+    fn pending_vote(&self, proposal: u64, voter: &ManagedAddress) -> SingleValueMapper<bool>;
+
+    #[view(getProposalEndNonce)]
+    #[storage_mapper("proposal_end_nonce")]
+    fn proposal_end_nonce(&self, proposal: u64) -> SingleValueMapper<u64>;
+
+    /// Proposals on which the user voted, and which might still lock their voting power.
+    #[storage_mapper("user_vote_locks")]
+    fn user_vote_locks(&self, voter: &ManagedAddress) -> UnorderedSetMapper<u64>;
+
+    /// The voting power snapshot taken when voting, locked while the proposal is registered and open.
+    #[storage_mapper("locked_voting_power")]
+    fn locked_voting_power(
+        &self,
+        proposal: u64,
+        voter: &ManagedAddress,
+    ) -> SingleValueMapper<BigUint>;
+
+    #[view(getRepresentative)]
+    #[storage_mapper("representative")]
+    fn representative(&self, user: &ManagedAddress) -> SingleValueMapper<ManagedAddress>;
+
+    #[view(getRepresentedUsers)]
+    #[storage_mapper("represented_users")]
+    fn represented_users(
+        &self,
+        representative: &ManagedAddress,
+    ) -> UnorderedSetMapper<ManagedAddress>;
+
+    /// The represented users whose votes a representative forwarded, until the result arrives.
+    #[storage_mapper("represented_voters")]
+    fn represented_voters(
+        &self,
+        proposal: u64,
+        representative: &ManagedAddress,
+    ) -> SingleValueMapper<ManagedVec<ManagedAddress>>;
+
+    #[view(getPoolVote)]
+    #[storage_mapper("pool_vote")]
+    fn pool_vote(&self, proposal: u64) -> SingleValueMapper<PoolVote<Self::Api>>;
+
+    /// Voting power of the users who voted themselves on a proposal, including votes still pending.
+    #[storage_mapper("proposal_user_voted_power")]
+    fn proposal_user_voted_power(&self, proposal: u64) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("proposal_vote_options")]
+    fn proposal_vote_options(&self, proposal: u64) -> UnorderedSetMapper<ManagedBuffer>;
+
+    #[storage_mapper("proposal_tally")]
+    fn proposal_tally_by_option(
+        &self,
+        proposal: u64,
+        vote: &ManagedBuffer,
+    ) -> SingleValueMapper<BigUint>;
 }
@@ -1,10 +1,27 @@
-use crate::auction_proxy::AuctionProxy;
+use crate::{auction_proxy::AuctionProxy, settings::OWNER_USER_ID};
 use node_storage::{
-    node_config::NodeIndexArrayVec,
+    node_config::{NodeIndexArrayVec, MAX_NODES_PER_OPERATION},
     types::{BLSKey, BLSSignature, BLSStatusMultiArg, NodeState},
 };
 
 multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+const AUCTION_STATUS_STAKED: &[u8] = b"staked";
+const AUCTION_STATUS_JAILED: &[u8] = b"jailed";
+const AUCTION_STATUS_QUEUED: &[u8] = b"queued";
+const AUCTION_STATUS_AUCTION: &[u8] = b"auction";
+const AUCTION_STATUS_UNSTAKED: &[u8] = b"unStaked";
+
+/// Stake of the contract, as last reported by the auction SC.
+#[type_abi]
+#[derive(TopEncode, TopDecode, PartialEq, Debug)]
+pub struct AuctionStakeReport<M: ManagedTypeApi> {
+    pub top_up: BigUint<M>,
+    pub total_staked: BigUint<M>,
+    pub num_staked_nodes: u64,
+    pub reported_at: u64,
+}
 
 #[multiversx_sc::derive::module]
 pub trait NodeActivationModule:
@@ -18,6 +35,10 @@ pub trait NodeActivationModule:
     + crate::rewards_state::RewardStateModule
     + crate::user_stake_state::UserStakeStateModule
     + crate::events::EventsModule
+    + crate::node_jail_history::NodeJailHistoryModule
+    + crate::sunset_state::SunsetStateModule
+    + multiversx_sc_modules::pause::PauseModule
+    + crate::emergency_mode_state::EmergencyModeStateModule
 {
     /// Owner activates specific nodes.
     #[only_owner]
@@ -27,6 +48,89 @@ pub trait NodeActivationModule:
         amount_to_stake: BigUint,
         bls_keys: MultiValueManagedVec<Self::Api, BLSKey<Self::Api>>,
     ) {
+        self.require_can_stake_nodes(&amount_to_stake);
+
+        let mut node_ids = NodeIndexArrayVec::new();
+        let mut bls_keys_signatures: MultiValueEncoded<
+            Self::Api,
+            MultiValue2<BLSKey<Self::Api>, BLSSignature<Self::Api>>,
+        > = MultiValueEncoded::new();
+
+        for bls_key in bls_keys.into_iter() {
+            let node_id = self.get_node_id(&bls_key);
+            require!(node_id != 0, "unknown node provided");
+
+            require!(
+                self.get_node_state(node_id) == NodeState::Inactive,
+                "node must be inactive"
+            );
+
+            self.prepare_node_for_staking(
+                node_id,
+                bls_key,
+                &mut node_ids,
+                &mut bls_keys_signatures,
+            );
+        }
+
+        self.perform_stake_nodes(node_ids, bls_keys_signatures, amount_to_stake);
+    }
+
+    /// Stakes the next inactive nodes from the auto-stake queue, as many as the available funds allow.
+    /// Each node is staked with the configured auto-stake amount per node.
+    /// Can be called by anyone, since both the nodes and the amounts are decided by the owner.
+    #[endpoint(autoStakeNodes)]
+    fn auto_stake_nodes(&self) {
+        let stake_per_node = self.auto_stake_per_node().get();
+        require!(
+            stake_per_node > 0,
+            "automatic node staking is not configured"
+        );
+
+        let num_fundable_nodes = self.total_unprotected() / &stake_per_node;
+        let max_nodes = num_fundable_nodes
+            .to_u64()
+            .map_or(MAX_NODES_PER_OPERATION, |n| {
+                core::cmp::min(n as usize, MAX_NODES_PER_OPERATION)
+            });
+        require!(max_nodes > 0, "not enough funds in contract to stake nodes");
+
+        let mut node_ids = NodeIndexArrayVec::new();
+        let mut bls_keys_signatures: MultiValueEncoded<
+            Self::Api,
+            MultiValue2<BLSKey<Self::Api>, BLSSignature<Self::Api>>,
+        > = MultiValueEncoded::new();
+
+        for node_id in self.auto_stake_queue().iter() {
+            if node_ids.len() == max_nodes {
+                break;
+            }
+
+            if self.get_node_state(node_id) == NodeState::Inactive {
+                let bls_key = self.get_node_id_to_bls(node_id);
+                self.prepare_node_for_staking(
+                    node_id,
+                    bls_key,
+                    &mut node_ids,
+                    &mut bls_keys_signatures,
+                );
+            }
+        }
+        require!(
+            !node_ids.is_empty(),
+            "no inactive nodes in the auto-stake queue"
+        );
+
+        let amount_to_stake = stake_per_node * node_ids.len() as u64;
+        self.require_can_stake_nodes(&amount_to_stake);
+
+        self.perform_stake_nodes(node_ids, bls_keys_signatures, amount_to_stake);
+    }
+
+    fn require_can_stake_nodes(&self, amount_to_stake: &BigUint) {
+        self.require_operational();
+        self.require_not_sunset();
+
         require!(
             !self.is_bootstrap_mode(),
             "cannot stake nodes in bootstrap mode"
@@ -38,37 +142,67 @@ pub trait NodeActivationModule:
         );
 
         require!(
-            self.total_unprotected() >= amount_to_stake,
+            &self.total_unprotected() >= amount_to_stake,
             "not enough funds in contract to stake nodes"
         );
 
         self.validate_owner_stake_share();
+    }
 
-        let mut node_ids = NodeIndexArrayVec::new();
-        let mut bls_keys_signatures: MultiValueEncoded<
-            Self::Api,
+    fn prepare_node_for_staking(
+        &self,
+        node_id: usize,
+        bls_key: BLSKey<Self::Api>,
+        node_ids: &mut NodeIndexArrayVec,
+        bls_keys_signatures: &mut MultiValueEncoded<
             MultiValue2<BLSKey<Self::Api>, BLSSignature<Self::Api>>,
-        > = MultiValueEncoded::new();
+        >,
+    ) {
+        node_ids.push(node_id);
+        let bls_signature = self.get_node_signature(node_id);
+        bls_keys_signatures.push((bls_key, bls_signature).into());
+
+        self.set_node_state(node_id, NodeState::PendingActivation);
+    }
+
+    /// The amount each node is staked with by `autoStakeNodes`.
+    /// Setting it to 0 disables automatic node staking.
+    #[only_owner]
+    #[endpoint(setAutoStakePerNode)]
+    fn set_auto_stake_per_node(&self, stake_per_node: BigUint) {
+        self.auto_stake_per_node().set(stake_per_node);
+    }
 
+    /// Replaces the list of nodes that `autoStakeNodes` can stake, in the order in which they get staked.
+    /// Nodes that are not inactive when their turn comes are skipped.
+    #[only_owner]
+    #[endpoint(setAutoStakeQueue)]
+    fn set_auto_stake_queue(&self, bls_keys: MultiValueEncoded<BLSKey<Self::Api>>) {
+        let mut auto_stake_queue = self.auto_stake_queue();
+        auto_stake_queue.clear();
         for bls_key in bls_keys.into_iter() {
             let node_id = self.get_node_id(&bls_key);
             require!(node_id != 0, "unknown node provided");
-
-            require!(
-                self.get_node_state(node_id) == NodeState::Inactive,
-                "node must be inactive"
-            );
-
-            node_ids.push(node_id);
-            let bls_signature = self.get_node_signature(node_id);
-            bls_keys_signatures.push((bls_key, bls_signature).into());
-
-            self.set_node_state(node_id, NodeState::PendingActivation);
+            auto_stake_queue.push(&node_id);
         }
+    }
 
-        self.perform_stake_nodes(node_ids, bls_keys_signatures, amount_to_stake);
+    #[view(getAutoStakeQueue)]
+    fn get_auto_stake_queue(&self) -> MultiValueEncoded<BLSKey<Self::Api>> {
+        let mut result = MultiValueEncoded::new();
+        for node_id in self.auto_stake_queue().iter() {
+            result.push(self.get_node_id_to_bls(node_id));
+        }
+        result
     }
 
+    #[view(getAutoStakePerNode)]
+    #[storage_mapper("auto_stake_per_node")]
+    fn auto_stake_per_node(&self) -> SingleValueMapper<BigUint>;
+
+    #[storage_mapper("auto_stake_queue")]
+    fn auto_stake_queue(&self) -> VecMapper<usize>;
+
     fn perform_stake_nodes(
         &self,
         node_ids: NodeIndexArrayVec,
@@ -145,6 +279,148 @@ pub trait NodeActivationModule:
         self.stake_node_fail_event(err_msg);
     }
 
+    // TOP-UP
+
+    /// Sends unprotected funds to the auction SC as top-up for the already active nodes,
+    /// without staking new nodes.
+    #[only_owner]
+    #[endpoint(stakeTopUp)]
+    fn stake_top_up(&self, amount: BigUint) {
+        require!(amount > 0, "top-up amount must be positive");
+        self.require_can_stake_nodes(&amount);
+        require!(
+            !self
+                .nodes_by_state(NodeState::Active.discriminant())
+                .is_empty(),
+            "no active nodes to top up"
+        );
+
+        let auction_contract_addr = self.get_auction_contract_address();
+        self.tx()
+            .to(auction_contract_addr)
+            .typed(AuctionProxy)
+            .stake_top_up()
+            .egld(amount.clone())
+            .with_callback(self.callbacks().auction_top_up_callback(amount))
+            .async_call_and_exit()
+    }
+
+    #[callback]
+    fn auction_top_up_callback(
+        &self,
+        amount: BigUint,
+        #[call_result] call_result: ManagedAsyncCallResult<()>,
+    ) {
+        match call_result {
+            ManagedAsyncCallResult::Ok(()) => {
+                self.total_top_up()
+                    .update(|total_top_up| *total_top_up += &amount);
+                self.top_up_stake_ok_event(&amount);
+            }
+            ManagedAsyncCallResult::Err(error) => {
+                // the funds are returned with the failed call
+                self.top_up_stake_fail_event(&error.err_msg);
+            }
+        }
+    }
+
+    /// Total EGLD sent to the auction SC as top-up via `stakeTopUp`.
+    /// See `syncAuctionStake` for the top-up as seen by the auction SC.
+    #[view(getTotalTopUp)]
+    #[storage_mapper("total_top_up")]
+    fn total_top_up(&self) -> SingleValueMapper<BigUint>;
+
+    /// Unstakes part of the top-up from the auction SC.
+    /// It can be reclaimed with `unBondTopUp` once the unbond period has passed.
+    #[only_owner]
+    #[endpoint(unStakeTopUp)]
+    fn unstake_top_up(&self, amount: BigUint) {
+        self.require_operational();
+        require!(amount > 0, "top-up amount must be positive");
+        require!(amount <= self.total_top_up().get(), "not enough top-up");
+
+        self.perform_unstake_top_up(amount)
+    }
+
+    fn perform_unstake_top_up(&self, amount: BigUint) {
+        // deducted right away, so that calls pending at the same time cannot unstake more than the total
+        self.total_top_up()
+            .update(|total_top_up| *total_top_up -= &amount);
+
+        let auction_contract_addr = self.get_auction_contract_address();
+        self.tx()
+            .to(auction_contract_addr)
+            .typed(AuctionProxy)
+            .unstake_tokens(&amount)
+            .with_callback(self.callbacks().auction_unstake_top_up_callback(amount))
+            .async_call_and_exit()
+    }
+
+    #[callback]
+    fn auction_unstake_top_up_callback(
+        &self,
+        amount: BigUint,
+        #[call_result] call_result: ManagedAsyncCallResult<()>,
+    ) {
+        match call_result {
+            ManagedAsyncCallResult::Ok(()) => {
+                self.unstaked_top_up()
+                    .update(|unstaked_top_up| *unstaked_top_up += &amount);
+                self.top_up_unstake_ok_event(&amount);
+            }
+            ManagedAsyncCallResult::Err(error) => {
+                self.total_top_up()
+                    .update(|total_top_up| *total_top_up += &amount);
+                self.top_up_unstake_fail_event(&error.err_msg);
+            }
+        }
+    }
+
+    /// Reclaims all the unstaked top-up from the auction SC.
+    /// The funds return to the contract as unprotected funds.
+    #[only_owner]
+    #[endpoint(unBondTopUp)]
+    fn unbond_top_up(&self) {
+        self.require_operational();
+        require!(!self.unstaked_top_up().is_empty(), "no unstaked top-up");
+
+        self.perform_unbond_top_up()
+    }
+
+    fn perform_unbond_top_up(&self) {
+        let amount = self.unstaked_top_up().take();
+        let auction_contract_addr = self.get_auction_contract_address();
+        self.tx()
+            .to(auction_contract_addr)
+            .typed(AuctionProxy)
+            .unbond_tokens(&amount)
+            .with_callback(self.callbacks().auction_unbond_top_up_callback(amount))
+            .async_call_and_exit()
+    }
+
+    #[callback]
+    fn auction_unbond_top_up_callback(
+        &self,
+        amount: BigUint,
+        #[call_result] call_result: ManagedAsyncCallResult<()>,
+    ) {
+        match call_result {
+            ManagedAsyncCallResult::Ok(()) => {
+                self.top_up_unbond_ok_event(&amount);
+            }
+            ManagedAsyncCallResult::Err(error) => {
+                self.unstaked_top_up()
+                    .update(|unstaked_top_up| *unstaked_top_up += &amount);
+                self.top_up_unbond_fail_event(&error.err_msg);
+            }
+        }
+    }
+
+    /// Top-up unstaked from the auction SC, by `unStakeTopUp` or by the sunset, not yet reclaimed.
+    #[view(getUnStakedTopUp)]
+    #[storage_mapper("unstaked_top_up")]
+    fn unstaked_top_up(&self) -> SingleValueMapper<BigUint>;
+
     // UNSTAKE
 
     /// Unstakes from the auction smart contract.
@@ -175,6 +451,7 @@ pub trait NodeActivationModule:
         unstake_tokens: bool,
         bls_keys: MultiValueManagedVec<Self::Api, BLSKey<Self::Api>>,
     ) {
+        self.require_operational();
         require!(
             !self.is_global_op_in_progress(),
             "node operations are temporarily paused as checkpoint is reset"
@@ -304,6 +581,7 @@ pub trait NodeActivationModule:
         &self,
         bls_keys: MultiValueManagedVec<Self::Api, BLSKey<Self::Api>>,
     ) {
+        self.require_operational();
         require!(
             !self.is_global_op_in_progress(),
             "node operations are temporarily paused as checkpoint is reset"
@@ -330,21 +608,29 @@ pub trait NodeActivationModule:
     #[only_owner]
     #[endpoint(unBondAllPossibleNodes)]
     fn unbond_all_possible_nodes(&self) {
+        self.require_operational();
         require!(
             !self.is_global_op_in_progress(),
             "node operations are temporarily paused as checkpoint is reset"
         );
 
-        let mut node_id = self.num_nodes().get();
+        // collect first, preparing the nodes moves them out of the UnBondPeriod index
+        let mut candidate_ids = NodeIndexArrayVec::new();
+        for node_id in self
+            .nodes_by_state(NodeState::UnBondPeriod { started: 0 }.discriminant())
+            .iter()
+            .take(MAX_NODES_PER_OPERATION)
+        {
+            candidate_ids.push(node_id);
+        }
+
         let mut node_ids = NodeIndexArrayVec::new();
         let mut bls_keys = MultiValueManagedVec::<Self::Api, BLSKey<Self::Api>>::new();
-        while node_id >= 1 {
+        for node_id in candidate_ids {
             if self.prepare_node_for_unbond_if_possible(node_id) {
                 node_ids.push(node_id);
                 bls_keys.push(self.get_node_id_to_bls(node_id));
             }
-
-            node_id -= 1;
         }
 
         if !node_ids.is_empty() {
@@ -352,6 +638,31 @@ pub trait NodeActivationModule:
         }
     }
 
+    /// Collects at most `max_nodes` nodes in the given state.
+    /// Collected first, because changing the node state moves them out of the state index.
+    fn first_nodes_in_state(&self, state: NodeState, max_nodes: usize) -> NodeIndexArrayVec {
+        let mut node_ids = NodeIndexArrayVec::new();
+        for node_id in self
+            .nodes_by_state(state.discriminant())
+            .iter()
+            .take(core::cmp::min(max_nodes, MAX_NODES_PER_OPERATION))
+        {
+            node_ids.push(node_id);
+        }
+        node_ids
+    }
+
+    fn bls_keys_of_nodes(
+        &self,
+        node_ids: &NodeIndexArrayVec,
+    ) -> MultiValueManagedVec<Self::Api, BLSKey<Self::Api>> {
+        let mut bls_keys = MultiValueManagedVec::new();
+        for &node_id in node_ids.iter() {
+            bls_keys.push(self.get_node_id_to_bls(node_id));
+        }
+        bls_keys
+    }
+
     fn prepare_node_for_unbond_if_possible(&self, node_id: usize) -> bool {
         if let NodeState::UnBondPeriod { started } = self.get_node_state(node_id) {
             self.set_node_state(
@@ -445,10 +756,312 @@ pub trait NodeActivationModule:
         self.unbond_node_fail_event(err_msg);
     }
 
+    // STUCK NODES
+
+    /// Number of blocks after which a node in a pending state is considered stuck,
+    /// i.e. the auction callback was most likely lost.
+    #[only_owner]
+    #[endpoint(setPendingNodeTimeout)]
+    fn set_pending_node_timeout(&self, timeout_blocks: u64) {
+        require!(timeout_blocks > 0, "pending node timeout must be positive");
+        self.pending_node_timeout().set(timeout_blocks);
+    }
+
+    #[view(getPendingNodeTimeout)]
+    #[storage_mapper("pending_node_timeout")]
+    fn pending_node_timeout(&self) -> SingleValueMapper<u64>;
+
+    /// Moves nodes stuck in a pending state for longer than the timeout out of it.
+    /// The auction SC is queried for the status of our BLS keys, which decides where each node goes:
+    /// - staked, jailed, queued or in the auction: `Active`,
+    /// - unstaked: `UnBondPeriod`, counted from the unstake call for `PendingUnBond` and `PendingDeactivation`,
+    /// - unknown to the auction SC: `Inactive`.
+    #[only_owner]
+    #[endpoint(recoverStuckNodes)]
+    fn recover_stuck_nodes(&self, bls_keys: MultiValueManagedVec<Self::Api, BLSKey<Self::Api>>) {
+        self.require_operational();
+        require!(
+            !self.is_global_op_in_progress(),
+            "node operations are temporarily paused as checkpoint is reset"
+        );
+
+        let timeout = self.pending_node_timeout().get();
+        require!(timeout > 0, "pending node timeout not configured");
+
+        let current_nonce = self.blockchain().get_block_nonce();
+        let mut node_ids = ManagedVec::<Self::Api, usize>::new();
+        for bls_key in bls_keys.iter() {
+            let node_id = self.get_node_id(&bls_key);
+            require!(node_id != 0, "unknown node provided");
+            require!(
+                self.get_node_state(node_id).is_pending(),
+                "node not in a pending state"
+            );
+            let pending_since = self.node_pending_since(node_id).get();
+            require!(
+                current_nonce >= pending_since + timeout,
+                "pending node timeout not reached"
+            );
+
+            node_ids.push(node_id);
+        }
+
+        let auction_contract_addr = self.get_auction_contract_address();
+        let sc_address = self.blockchain().get_sc_address();
+        self.tx()
+            .to(auction_contract_addr)
+            .typed(AuctionProxy)
+            .get_bls_keys_status(sc_address)
+            .with_callback(self.callbacks().recover_stuck_nodes_callback(node_ids))
+            .async_call_and_exit()
+    }
+
+    #[callback]
+    fn recover_stuck_nodes_callback(
+        &self,
+        node_ids: ManagedVec<usize>,
+        #[call_result] call_result: ManagedAsyncCallResult<
+            MultiValueEncoded<MultiValue2<ManagedBuffer, ManagedBuffer>>,
+        >,
+    ) {
+        let key_statuses = match call_result {
+            ManagedAsyncCallResult::Ok(key_statuses) => key_statuses,
+            ManagedAsyncCallResult::Err(error) => {
+                self.auction_query_fail_event(&error.err_msg);
+                return;
+            }
+        };
+
+        let mut reported_node_ids = ManagedVec::<Self::Api, usize>::new();
+        for key_status in key_statuses.into_iter() {
+            let (bls_key_bytes, status) = key_status.into_tuple();
+            let bls_key = match BLSKey::top_decode(bls_key_bytes) {
+                Ok(bls_key) => bls_key,
+                Err(_) => continue,
+            };
+            let node_id = self.get_node_id(&bls_key);
+            if node_ids.contains(&node_id) {
+                reported_node_ids.push(node_id);
+                self.recover_stuck_node(node_id, &bls_key, &status);
+            }
+        }
+
+        for node_id in node_ids.iter() {
+            if !reported_node_ids.contains(&node_id) {
+                let bls_key = self.get_node_id_to_bls(node_id);
+                self.recover_stuck_node(node_id, &bls_key, &ManagedBuffer::new());
+            }
+        }
+    }
+
+    fn recover_stuck_node(
+        &self,
+        node_id: usize,
+        bls_key: &BLSKey<Self::Api>,
+        auction_status: &ManagedBuffer,
+    ) {
+        let pending_state = self.get_node_state(node_id);
+        if !pending_state.is_pending() {
+            // the lost callback arrived in the meantime
+            return;
+        }
+
+        let new_state = if self.is_auction_status_staked(auction_status) {
+            NodeState::Active
+        } else if *auction_status == *AUCTION_STATUS_UNSTAKED {
+            let started = match pending_state {
+                NodeState::PendingUnBond { unbond_started } => unbond_started,
+                _ => self.node_pending_since(node_id).get(),
+            };
+            NodeState::UnBondPeriod { started }
+        } else {
+            NodeState::Inactive
+        };
+
+        self.set_node_state(node_id, new_state);
+        self.stuck_node_recovered_event(
+            bls_key,
+            pending_state.discriminant(),
+            new_state.discriminant(),
+        );
+    }
+
+    /// Whether the auction SC status means that the node is staked, even if not validating.
+    fn is_auction_status_staked(&self, auction_status: &ManagedBuffer) -> bool {
+        *auction_status == *AUCTION_STATUS_STAKED
+            || *auction_status == *AUCTION_STATUS_JAILED
+            || *auction_status == *AUCTION_STATUS_QUEUED
+            || *auction_status == *AUCTION_STATUS_AUCTION
+    }
+
+    // RECONCILE
+
+    /// Queries the auction SC for the status of our BLS keys
+    /// and corrects the local states of the given nodes where they drifted from it.
+    /// Nodes in a pending state are left alone, their callback might still be on the way.
+    /// Providers with more nodes than fit in one call reconcile them in batches.
+    #[only_owner]
+    #[endpoint(reconcileNodeStates)]
+    fn reconcile_node_states(&self, bls_keys: MultiValueManagedVec<Self::Api, BLSKey<Self::Api>>) {
+        self.require_operational();
+        require!(
+            !self.is_global_op_in_progress(),
+            "node operations are temporarily paused as checkpoint is reset"
+        );
+
+        let mut node_ids = NodeIndexArrayVec::new();
+        for bls_key in bls_keys.iter() {
+            let node_id = self.get_node_id(&bls_key);
+            require!(node_id != 0, "unknown node provided");
+            node_ids.push(node_id);
+        }
+
+        // sorted, so that the callback can look the reported nodes up
+        node_ids.sort_unstable();
+        require!(
+            node_ids.windows(2).all(|pair| pair[0] != pair[1]),
+            "duplicate node provided"
+        );
+
+        let auction_contract_addr = self.get_auction_contract_address();
+        let sc_address = self.blockchain().get_sc_address();
+        self.tx()
+            .to(auction_contract_addr)
+            .typed(AuctionProxy)
+            .get_bls_keys_status(sc_address)
+            .with_callback(self.callbacks().reconcile_node_states_callback(node_ids))
+            .async_call_and_exit()
+    }
+
+    #[callback]
+    fn reconcile_node_states_callback(
+        &self,
+        node_ids: NodeIndexArrayVec,
+        #[call_result] call_result: ManagedAsyncCallResult<
+            MultiValueEncoded<MultiValue2<ManagedBuffer, ManagedBuffer>>,
+        >,
+    ) {
+        let key_statuses = match call_result {
+            ManagedAsyncCallResult::Ok(key_statuses) => key_statuses,
+            ManagedAsyncCallResult::Err(error) => {
+                self.auction_query_fail_event(&error.err_msg);
+                return;
+            }
+        };
+
+        let mut reported = [false; MAX_NODES_PER_OPERATION];
+        for key_status in key_statuses.into_iter() {
+            let (bls_key_bytes, status) = key_status.into_tuple();
+            let bls_key = match BLSKey::top_decode(bls_key_bytes) {
+                Ok(bls_key) => bls_key,
+                Err(_) => continue,
+            };
+            let node_id = self.get_node_id(&bls_key);
+            if let Ok(index) = node_ids.binary_search(&node_id) {
+                reported[index] = true;
+                self.correct_node_state(node_id, &bls_key, &status);
+            }
+        }
+
+        // nodes the auction does not know about
+        for (index, &node_id) in node_ids.iter().enumerate() {
+            if !reported[index] {
+                let bls_key = self.get_node_id_to_bls(node_id);
+                self.correct_node_state(node_id, &bls_key, &ManagedBuffer::new());
+            }
+        }
+    }
+
+    fn correct_node_state(
+        &self,
+        node_id: usize,
+        bls_key: &BLSKey<Self::Api>,
+        auction_status: &ManagedBuffer,
+    ) {
+        let old_state = self.get_node_state(node_id);
+        if old_state.is_pending() || old_state == NodeState::Removed {
+            return;
+        }
+
+        let new_state = if self.is_auction_status_staked(auction_status) {
+            if *auction_status == *AUCTION_STATUS_JAILED {
+                self.record_node_jailed(node_id, bls_key);
+            }
+            NodeState::Active
+        } else if *auction_status == *AUCTION_STATUS_UNSTAKED {
+            match old_state {
+                NodeState::UnBondPeriod { .. } => old_state,
+                // the unstake nonce is unknown, the full unbond period is counted from now
+                _ => NodeState::UnBondPeriod {
+                    started: self.blockchain().get_block_nonce(),
+                },
+            }
+        } else {
+            NodeState::Inactive
+        };
+
+        if new_state != old_state {
+            self.set_node_state(node_id, new_state);
+            self.node_state_corrected_event(
+                bls_key,
+                old_state.discriminant(),
+                new_state.discriminant(),
+            );
+        }
+    }
+
+    /// Queries the auction SC for the total stake and top-up of the contract.
+    /// The result can be retrieved with `getAuctionStakeReport`.
+    #[only_owner]
+    #[endpoint(syncAuctionStake)]
+    fn sync_auction_stake(&self) {
+        let auction_contract_addr = self.get_auction_contract_address();
+        let sc_address = self.blockchain().get_sc_address();
+        self.tx()
+            .to(auction_contract_addr)
+            .typed(AuctionProxy)
+            .get_total_staked_top_up_staked_bls_keys(sc_address)
+            .with_callback(self.callbacks().sync_auction_stake_callback())
+            .async_call_and_exit()
+    }
+
+    #[callback]
+    fn sync_auction_stake_callback(
+        &self,
+        #[call_result] call_result: ManagedAsyncCallResult<MultiValueEncoded<ManagedBuffer>>,
+    ) {
+        let results = match call_result {
+            ManagedAsyncCallResult::Ok(results) => results.to_vec(),
+            ManagedAsyncCallResult::Err(error) => {
+                self.auction_query_fail_event(&error.err_msg);
+                return;
+            }
+        };
+        require!(results.len() >= 3, "unexpected auction stake report");
+
+        let top_up = BigUint::from_bytes_be_buffer(&results.get(0));
+        let total_staked = BigUint::from_bytes_be_buffer(&results.get(1));
+        let num_staked_nodes = BigUint::from_bytes_be_buffer(&results.get(2))
+            .to_u64()
+            .unwrap_or_default();
+        self.auction_stake_reported_event(&top_up, &total_staked, num_staked_nodes);
+        self.auction_stake_report().set(AuctionStakeReport {
+            top_up,
+            total_staked,
+            num_staked_nodes,
+            reported_at: self.blockchain().get_block_nonce(),
+        });
+    }
+
+    #[view(getAuctionStakeReport)]
+    #[storage_mapper("auction_stake_report")]
+    fn auction_stake_report(&self) -> SingleValueMapper<AuctionStakeReport<Self::Api>>;
+
     /// Claims from auction SC funds that were sent but are not required to run the nodes.
     #[only_owner]
     #[endpoint(claimUnusedFunds)]
     fn claim_unused_funds(&self) {
+        self.require_operational();
         require!(
             !self.is_global_op_in_progress(),
             "node operations are temporarily paused as checkpoint is reset"
@@ -471,7 +1084,49 @@ pub trait NodeActivationModule:
         bls_keys: MultiValueManagedVec<Self::Api, BLSKey<Self::Api>>,
         #[payment] fine_payment: BigUint,
     ) {
-        // validation only
+        let node_ids = self.validate_nodes_for_unjail(&bls_keys);
+        self.perform_unjail(node_ids, bls_keys, fine_payment, false);
+    }
+
+    /// Same as `unJailNodes`, but the fine is paid from the unclaimed rewards of the owner,
+    /// instead of a fresh payment.
+    #[only_owner]
+    #[endpoint(unJailNodesFromRewards)]
+    fn unjail_nodes_from_rewards(
+        &self,
+        fine_amount: BigUint,
+        bls_keys: MultiValueManagedVec<Self::Api, BLSKey<Self::Api>>,
+    ) {
+        require!(
+            !self.is_global_op_in_progress(),
+            "node operations are temporarily paused as checkpoint is reset"
+        );
+
+        let node_ids = self.validate_nodes_for_unjail(&bls_keys);
+
+        let mut owner_rewards = self.load_updated_user_rewards(OWNER_USER_ID);
+        require!(
+            owner_rewards.unclaimed_rewards >= fine_amount,
+            "not enough owner rewards to pay the fine"
+        );
+        owner_rewards.unclaimed_rewards -= &fine_amount;
+        self.store_user_reward_data(OWNER_USER_ID, &owner_rewards);
+
+        // from an accounting perspective, the fine is paid out to the owner as rewards
+        let sent_rewards = self.get_sent_rewards() + &fine_amount;
+        self.set_sent_rewards(&sent_rewards);
+
+        self.perform_unjail(node_ids, bls_keys, fine_amount, true);
+    }
+
+    fn validate_nodes_for_unjail(
+        &self,
+        bls_keys: &MultiValueManagedVec<Self::Api, BLSKey<Self::Api>>,
+    ) -> NodeIndexArrayVec {
+        self.require_operational();
+        require!(!bls_keys.is_empty(), "no BLS keys provided");
+
+        let mut node_ids = NodeIndexArrayVec::new();
         for bls_key in bls_keys.iter() {
             let node_id = self.get_node_id(&bls_key);
             require!(node_id != 0, "unknown node provided");
@@ -479,20 +1134,75 @@ pub trait NodeActivationModule:
                 self.get_node_state(node_id) == NodeState::Active,
                 "node must be active"
             );
+            node_ids.push(node_id);
         }
+        node_ids
+    }
 
+    fn perform_unjail(
+        &self,
+        node_ids: NodeIndexArrayVec,
+        bls_keys: MultiValueManagedVec<Self::Api, BLSKey<Self::Api>>,
+        fine: BigUint,
+        paid_from_rewards: bool,
+    ) {
         // send unJail command to Auction SC
         let auction_contract_addr = self.get_auction_contract_address();
         self.tx()
             .to(auction_contract_addr)
             .typed(AuctionProxy)
             .unjail(bls_keys)
-            .egld(fine_payment)
+            .egld(fine.clone())
+            .with_callback(self.callbacks().auction_unjail_callback(
+                node_ids,
+                fine,
+                paid_from_rewards,
+            ))
             .async_call_and_exit()
     }
 
+    #[callback]
+    fn auction_unjail_callback(
+        &self,
+        node_ids: NodeIndexArrayVec,
+        fine: BigUint,
+        paid_from_rewards: bool,
+        #[call_result] call_result: ManagedAsyncCallResult<()>,
+    ) {
+        match call_result {
+            ManagedAsyncCallResult::Ok(()) => {
+                // the fine is the same for all nodes, any remainder goes to the first one
+                let num_nodes = node_ids.len() as u64;
+                let fine_per_node = &fine / num_nodes;
+                let mut remainder = &fine - &(&fine_per_node * num_nodes);
+                for &node_id in node_ids.iter() {
+                    let bls_key = self.get_node_id_to_bls(node_id);
+                    let node_fine = &fine_per_node + &remainder;
+                    self.record_node_unjailed(node_id, &bls_key, &node_fine);
+                    remainder = BigUint::zero();
+                }
+            }
+            ManagedAsyncCallResult::Err(error) => {
+                // the fine came back with the failed call
+                if paid_from_rewards {
+                    let mut owner_rewards = self.load_updated_user_rewards(OWNER_USER_ID);
+                    owner_rewards.unclaimed_rewards += &fine;
+                    self.store_user_reward_data(OWNER_USER_ID, &owner_rewards);
+
+                    let sent_rewards = self.get_sent_rewards() - &fine;
+                    self.set_sent_rewards(&sent_rewards);
+                } else {
+                    let owner = self.blockchain().get_owner_address();
+                    self.tx().to(&owner).egld(&fine).transfer_if_not_empty();
+                }
+                self.unjail_node_fail_event(&error.err_msg);
+            }
+        }
+    }
+
     #[endpoint(unStakeTokens)]
     fn unstake_tokens(&self, amount: BigUint) {
+        self.require_operational();
         self.unstake_tokens_event(&amount);
         let auction_contract_addr = self.get_auction_contract_address();
         self.tx()
@@ -504,6 +1214,7 @@ pub trait NodeActivationModule:
 
     #[endpoint(unBondTokens)]
     fn unbond_tokens(&self, amount: BigUint) {
+        self.require_operational();
         self.unbond_tokens_event(&amount);
         let auction_contract_addr = self.get_auction_contract_address();
         self.tx()
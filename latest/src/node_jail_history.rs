@@ -0,0 +1,91 @@
+use node_storage::types::BLSKey;
+
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+/// Jail record of a node, kept as a measure of its reliability.
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone, Debug)]
+pub struct NodeJailHistory<M: ManagedTypeApi> {
+    /// Whether the node is currently known to be jailed.
+    pub jailed: bool,
+
+    pub jail_count: u32,
+
+    pub unjail_count: u32,
+
+    /// Zero if the node was never unjailed.
+    pub last_unjail_nonce: u64,
+
+    pub total_fines_paid: BigUint<M>,
+}
+
+impl<M: ManagedTypeApi> Default for NodeJailHistory<M> {
+    fn default() -> Self {
+        NodeJailHistory {
+            jailed: false,
+            jail_count: 0,
+            unjail_count: 0,
+            last_unjail_nonce: 0,
+            total_fines_paid: BigUint::zero(),
+        }
+    }
+}
+
+/// Keeps track of when nodes get jailed and unjailed, and how much it cost.
+/// Jailing is detected when reconciling with the auction SC, or implied by an unjail.
+#[multiversx_sc::derive::module]
+pub trait NodeJailHistoryModule:
+    node_storage::node_config::NodeConfigModule + crate::events::EventsModule
+{
+    #[storage_mapper("node_jail_history")]
+    fn node_jail_history(&self, node_id: usize) -> SingleValueMapper<NodeJailHistory<Self::Api>>;
+
+    fn get_node_jail_history_or_default(&self, node_id: usize) -> NodeJailHistory<Self::Api> {
+        let mapper = self.node_jail_history(node_id);
+        if mapper.is_empty() {
+            NodeJailHistory::default()
+        } else {
+            mapper.get()
+        }
+    }
+
+    #[view(getNodeJailHistory)]
+    fn get_node_jail_history_endpoint(
+        &self,
+        bls_key: BLSKey<Self::Api>,
+    ) -> OptionalValue<NodeJailHistory<Self::Api>> {
+        let node_id = self.get_node_id(&bls_key);
+        if node_id == 0 {
+            OptionalValue::None
+        } else {
+            OptionalValue::Some(self.get_node_jail_history_or_default(node_id))
+        }
+    }
+
+    /// Counts a new jail, unless the node is already known to be jailed.
+    fn record_node_jailed(&self, node_id: usize, bls_key: &BLSKey<Self::Api>) {
+        let mut history = self.get_node_jail_history_or_default(node_id);
+        if history.jailed {
+            return;
+        }
+
+        history.jailed = true;
+        history.jail_count += 1;
+        self.node_jail_history(node_id).set(history);
+        self.node_jailed_event(bls_key);
+    }
+
+    fn record_node_unjailed(&self, node_id: usize, bls_key: &BLSKey<Self::Api>, fine: &BigUint) {
+        // an unjail implies a jail, even if it was not detected
+        self.record_node_jailed(node_id, bls_key);
+
+        let mut history = self.node_jail_history(node_id).get();
+        history.jailed = false;
+        history.unjail_count += 1;
+        history.last_unjail_nonce = self.blockchain().get_block_nonce();
+        history.total_fines_paid += fine;
+        self.node_jail_history(node_id).set(history);
+        self.node_unjailed_event(bls_key, fine);
+    }
+}
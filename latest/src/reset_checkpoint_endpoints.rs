@@ -1,9 +1,13 @@
 use crate::reset_checkpoint_types::{
-    ComputeAllRewardsData, GlobalOpCheckpoint, ModifyDelegationCapStep,
-    ModifyTotalDelegationCapData,
+    CappedPerUserSwapData, ComputeAllRewardsData, GlobalOpCheckpoint, ModifyDelegationCapStep,
+    ModifyTotalDelegationCapData, ProRataSwapData, SunsetStep,
 };
-use crate::settings::{OWNER_USER_ID, PERCENTAGE_DENOMINATOR};
+use crate::settings::{
+    ForcedUnStakeMode, WaitingActivationMode, OWNER_USER_ID, PERCENTAGE_DENOMINATOR,
+};
+use crate::sunset_types::SunsetPhase;
 use core::cmp::Ordering;
+use user_fund_storage::fund_module::SwapDirection;
 use user_fund_storage::fund_view_module::USER_STAKE_TOTALS_ID;
 use user_fund_storage::types::FundType;
 
@@ -21,6 +25,10 @@ pub trait ResetCheckpointsModule:
     + user_fund_storage::fund_view_module::FundViewModule
     + user_fund_storage::fund_transf_module::FundTransformationsModule
     + crate::settings::SettingsModule
+    + crate::events::EventsModule
+    + crate::sunset_state::SunsetStateModule
+    + multiversx_sc_modules::pause::PauseModule
+    + crate::emergency_mode_state::EmergencyModeStateModule
 {
     /// Continues executing any interrupted operation.
     /// Returns true if still out of gas, false if computation completed.
@@ -77,6 +85,89 @@ pub trait ResetCheckpointsModule:
                     )
                 }
             }
+            GlobalOpCheckpoint::Sunset(sunset_step) => self.continue_sunset_step(sunset_step),
+        }
+    }
+
+    fn continue_sunset_step(
+        &self,
+        sunset_step: SunsetStep<Self::Api>,
+    ) -> (OperationCompletionStatus, GlobalOpCheckpoint<Self::Api>) {
+        let interrupt = || self.blockchain().get_gas_left() < STOP_AT_GASLIMIT;
+        match sunset_step {
+            SunsetStep::ComputeAllRewards(car_data) => {
+                if let Some(more_computation) = self.compute_all_rewards(car_data) {
+                    (
+                        OperationCompletionStatus::InterruptedBeforeOutOfGas,
+                        GlobalOpCheckpoint::Sunset(SunsetStep::ComputeAllRewards(more_computation)),
+                    )
+                } else {
+                    (
+                        OperationCompletionStatus::Completed,
+                        GlobalOpCheckpoint::Sunset(SunsetStep::SwapWaitingToDeferredPayment),
+                    )
+                }
+            }
+            SunsetStep::SwapWaitingToDeferredPayment => {
+                let mut remaining =
+                    self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Waiting);
+                self.swap_waiting_to_deferred_payment(&mut remaining, interrupt);
+                self.sunset_swap_outcome(
+                    &remaining,
+                    SunsetStep::SwapWaitingToDeferredPayment,
+                    SunsetStep::SwapUnstakedToDeferredPayment,
+                )
+            }
+            SunsetStep::SwapUnstakedToDeferredPayment => {
+                let mut remaining =
+                    self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::UnStaked);
+                self.swap_unstaked_to_deferred_payment(&mut remaining, interrupt);
+                self.sunset_swap_outcome(
+                    &remaining,
+                    SunsetStep::SwapUnstakedToDeferredPayment,
+                    SunsetStep::SwapActiveToDeferredPayment,
+                )
+            }
+            SunsetStep::SwapActiveToDeferredPayment => {
+                let mut remaining =
+                    self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Active);
+                self.swap_active_to_deferred_payment(&mut remaining, interrupt);
+                if remaining > 0 {
+                    return (
+                        OperationCompletionStatus::InterruptedBeforeOutOfGas,
+                        GlobalOpCheckpoint::Sunset(SunsetStep::SwapActiveToDeferredPayment),
+                    );
+                }
+
+                // finish, nothing is left staked
+                self.set_total_delegation_cap(BigUint::zero());
+                self.set_bootstrap_mode(false);
+                self.set_sunset_phase(SunsetPhase::Withdrawing);
+                (
+                    OperationCompletionStatus::Completed,
+                    GlobalOpCheckpoint::None,
+                )
+            }
+        }
+    }
+
+    /// Stays on the current step while there is something left to swap.
+    fn sunset_swap_outcome(
+        &self,
+        remaining: &BigUint,
+        current_step: SunsetStep<Self::Api>,
+        next_step: SunsetStep<Self::Api>,
+    ) -> (OperationCompletionStatus, GlobalOpCheckpoint<Self::Api>) {
+        if *remaining > 0 {
+            (
+                OperationCompletionStatus::InterruptedBeforeOutOfGas,
+                GlobalOpCheckpoint::Sunset(current_step),
+            )
+        } else {
+            (
+                OperationCompletionStatus::Completed,
+                GlobalOpCheckpoint::Sunset(next_step),
+            )
         }
     }
 
@@ -93,7 +184,9 @@ pub trait ResetCheckpointsModule:
                         GlobalOpCheckpoint::ModifyTotalDelegationCap(mdcap_data),
                     )
                 } else {
-                    mdcap_data.step = ModifyDelegationCapStep::SwapWaitingToActive;
+                    mdcap_data.step = self.start_swap_waiting_to_active_step(
+                        &mdcap_data.remaining_swap_waiting_to_active,
+                    );
                     (
                         OperationCompletionStatus::Completed,
                         GlobalOpCheckpoint::ModifyTotalDelegationCap(mdcap_data),
@@ -118,6 +211,56 @@ pub trait ResetCheckpointsModule:
                     )
                 }
             }
+            ModifyDelegationCapStep::SwapWaitingToActiveProRata(mut pro_rata_data) => {
+                self.swap_waiting_to_active_pro_rata(
+                    &mut pro_rata_data.current_id, // advances through the waiting list
+                    &mut mdcap_data.remaining_swap_waiting_to_active, // decreases this field directly
+                    &pro_rata_data.total_swap,
+                    &pro_rata_data.total_source,
+                    || self.blockchain().get_gas_left() < STOP_AT_GASLIMIT,
+                );
+                if pro_rata_data.current_id > 0 {
+                    mdcap_data.step =
+                        ModifyDelegationCapStep::SwapWaitingToActiveProRata(pro_rata_data);
+                    (
+                        OperationCompletionStatus::InterruptedBeforeOutOfGas,
+                        GlobalOpCheckpoint::ModifyTotalDelegationCap(mdcap_data),
+                    )
+                } else {
+                    // rounding leaves a small remainder, which is swapped the regular way
+                    mdcap_data.step = ModifyDelegationCapStep::SwapWaitingToActive;
+                    (
+                        OperationCompletionStatus::Completed,
+                        GlobalOpCheckpoint::ModifyTotalDelegationCap(mdcap_data),
+                    )
+                }
+            }
+            ModifyDelegationCapStep::SwapWaitingToActiveCappedPerUser(mut capped_data) => {
+                self.swap_waiting_to_active_capped_per_user(
+                    &capped_data.user_ids,
+                    &mut capped_data.next_index, // advances through the users
+                    &mut mdcap_data.remaining_swap_waiting_to_active, // decreases this field directly
+                    &capped_data.max_per_user,
+                    || self.blockchain().get_gas_left() < STOP_AT_GASLIMIT,
+                );
+                if capped_data.next_index < capped_data.user_ids.len()
+                    && mdcap_data.remaining_swap_waiting_to_active > 0
+                {
+                    mdcap_data.step =
+                        ModifyDelegationCapStep::SwapWaitingToActiveCappedPerUser(capped_data);
+                    (
+                        OperationCompletionStatus::InterruptedBeforeOutOfGas,
+                        GlobalOpCheckpoint::ModifyTotalDelegationCap(mdcap_data),
+                    )
+                } else {
+                    // whatever exceeded the caps is swapped the regular way
+                    mdcap_data.step = ModifyDelegationCapStep::SwapWaitingToActive;
+                    (
+                        OperationCompletionStatus::Completed,
+                        GlobalOpCheckpoint::ModifyTotalDelegationCap(mdcap_data),
+                    )
+                }
+            }
             ModifyDelegationCapStep::SwapUnstakedToDeferredPayment => {
                 self.swap_unstaked_to_deferred_payment(
                     &mut mdcap_data.remaining_swap_unstaked_to_def_p, // decreases this field directly
@@ -129,7 +272,9 @@ pub trait ResetCheckpointsModule:
                         GlobalOpCheckpoint::ModifyTotalDelegationCap(mdcap_data),
                     )
                 } else {
-                    mdcap_data.step = ModifyDelegationCapStep::SwapActiveToDeferredPayment;
+                    mdcap_data.step = self.start_swap_active_to_deferred_payment_step(
+                        &mdcap_data.remaining_swap_active_to_def_p,
+                    );
                     (
                         OperationCompletionStatus::Completed,
                         GlobalOpCheckpoint::ModifyTotalDelegationCap(mdcap_data),
@@ -155,6 +300,83 @@ pub trait ResetCheckpointsModule:
                     )
                 }
             }
+            ModifyDelegationCapStep::SwapActiveToDeferredPaymentProRata(mut pro_rata_data) => {
+                self.swap_active_to_deferred_payment_pro_rata(
+                    &mut pro_rata_data.current_id, // advances through the active list
+                    &mut mdcap_data.remaining_swap_active_to_def_p, // decreases this field directly
+                    &pro_rata_data.total_swap,
+                    &pro_rata_data.total_source,
+                    || self.blockchain().get_gas_left() < STOP_AT_GASLIMIT,
+                );
+                if pro_rata_data.current_id > 0 {
+                    mdcap_data.step =
+                        ModifyDelegationCapStep::SwapActiveToDeferredPaymentProRata(pro_rata_data);
+                    (
+                        OperationCompletionStatus::InterruptedBeforeOutOfGas,
+                        GlobalOpCheckpoint::ModifyTotalDelegationCap(mdcap_data),
+                    )
+                } else {
+                    // rounding leaves a small remainder, which is swapped the regular way
+                    mdcap_data.step = ModifyDelegationCapStep::SwapActiveToDeferredPayment;
+                    (
+                        OperationCompletionStatus::Completed,
+                        GlobalOpCheckpoint::ModifyTotalDelegationCap(mdcap_data),
+                    )
+                }
+            }
+        }
+    }
+
+    /// Chooses how Waiting stake is activated, based on the current waiting activation mode.
+    fn start_swap_waiting_to_active_step(
+        &self,
+        swap_waiting_to_active: &BigUint,
+    ) -> ModifyDelegationCapStep<Self::Api> {
+        if *swap_waiting_to_active == 0 {
+            return ModifyDelegationCapStep::SwapWaitingToActive;
+        }
+
+        match self.waiting_activation_mode().get() {
+            WaitingActivationMode::FirstInFirstOut => ModifyDelegationCapStep::SwapWaitingToActive,
+            WaitingActivationMode::ProRata => {
+                ModifyDelegationCapStep::SwapWaitingToActiveProRata(ProRataSwapData {
+                    total_swap: swap_waiting_to_active.clone(),
+                    total_source: self
+                        .get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Waiting),
+                    current_id: self.first_id_of_type(FundType::Waiting, SwapDirection::Forwards),
+                })
+            }
+            WaitingActivationMode::CappedPerUser { max_per_user } => {
+                ModifyDelegationCapStep::SwapWaitingToActiveCappedPerUser(CappedPerUserSwapData {
+                    max_per_user,
+                    user_ids: self.get_waiting_user_ids(),
+                    next_index: 0,
+                })
+            }
+        }
+    }
+
+    /// Chooses how Active stake is forced out, based on the current forced unstake mode.
+    fn start_swap_active_to_deferred_payment_step(
+        &self,
+        swap_active_to_def_p: &BigUint,
+    ) -> ModifyDelegationCapStep<Self::Api> {
+        match self.forced_unstake_mode().get() {
+            ForcedUnStakeMode::LastInFirstOut => {
+                ModifyDelegationCapStep::SwapActiveToDeferredPayment
+            }
+            ForcedUnStakeMode::ProRata => {
+                if *swap_active_to_def_p == 0 {
+                    return ModifyDelegationCapStep::SwapActiveToDeferredPayment;
+                }
+
+                ModifyDelegationCapStep::SwapActiveToDeferredPaymentProRata(ProRataSwapData {
+                    total_swap: swap_active_to_def_p.clone(),
+                    total_source: self
+                        .get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Active),
+                    current_id: self.first_id_of_type(FundType::Active, SwapDirection::Forwards),
+                })
+            }
         }
     }
 
@@ -205,16 +427,15 @@ pub trait ResetCheckpointsModule:
         None
     }
 
-    /// Total delegation cap can be modified by owner only.
-    /// It will recalculate and set the checkpoint for all the delegators
-    #[only_owner]
-    #[endpoint(modifyTotalDelegationCap)]
-    fn modify_total_delegation_cap(&self, new_total_cap: BigUint) -> OperationCompletionStatus {
-        require!(
-            !self.is_global_op_in_progress(),
-            "cannot modify total delegation cap when last is in progress"
-        );
-
+    /// Computes how much stake has to move when the total delegation cap changes, without changing anything.
+    /// Yields `None` if the contract remains in bootstrap mode.
+    /// Otherwise yields the amounts to swap, in order:
+    /// Waiting -> Active, UnStaked -> DeferredPayment and Active -> DeferredPayment.
+    /// All amounts are zero if nothing needs to be swapped.
+    fn delegation_cap_change_swap_amounts(
+        &self,
+        new_total_cap: &BigUint,
+    ) -> Option<(BigUint, BigUint, BigUint)> {
         let total_waiting = self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Waiting);
         let total_active = self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Active);
         let total_unstaked = self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::UnStaked);
@@ -222,29 +443,27 @@ pub trait ResetCheckpointsModule:
         let previous_total_cap: BigUint;
         let max_available = &(&total_active + &total_waiting) + &total_unstaked;
         if self.is_bootstrap_mode() {
-            if new_total_cap > max_available {
+            if *new_total_cap > max_available {
                 // we remain in bootstrap mode
                 // and so nothing else to be done here:
                 // compute all rewards not necessary - no rewards yet
                 // swap not necessary - there cannot be any waiting or unstaked funds
-                self.set_total_delegation_cap(new_total_cap);
-                return OperationCompletionStatus::Completed;
-            } else {
-                // bootstrap mode is over
-                // no rewards to compute, but
-                // swap might be necessary
-                self.set_bootstrap_mode(false);
-
-                // This scenario is equivalent to performing 2 operations:
-                // 1. drop from the previous delegation cap to max_amount - nothing happens to the funds.
-                // 2. drop from max_amount to the new_total_cap. This might involve some swaps.
-                // From here on, only step 2. needs to be performed, so we set the previous cap to what te max_amount was.
-                previous_total_cap = max_available;
+                return None;
             }
+
+            // bootstrap mode is over
+            // no rewards to compute, but
+            // swap might be necessary
+
+            // This scenario is equivalent to performing 2 operations:
+            // 1. drop from the previous delegation cap to max_amount - nothing happens to the funds.
+            // 2. drop from max_amount to the new_total_cap. This might involve some swaps.
+            // From here on, only step 2. needs to be performed, so we set the previous cap to what te max_amount was.
+            previous_total_cap = max_available;
         } else {
             // if no longer in bootstrap mode, total delegation cap can never exceed the max available
             require!(
-                new_total_cap <= max_available,
+                *new_total_cap <= max_available,
                 "new delegation cap must be less or equal to total active + waiting"
             );
 
@@ -252,10 +471,10 @@ pub trait ResetCheckpointsModule:
             previous_total_cap = self.get_total_delegation_cap();
         }
 
-        let orc = match new_total_cap.cmp(&previous_total_cap) {
+        let amounts = match new_total_cap.cmp(&previous_total_cap) {
             Ordering::Equal => {
                 // nothing changes
-                return OperationCompletionStatus::Completed;
+                (BigUint::zero(), BigUint::zero(), BigUint::zero())
             }
             Ordering::Greater => {
                 // cap increases
@@ -264,52 +483,136 @@ pub trait ResetCheckpointsModule:
                     "no unstaked funds should be present when increasing delegation cap"
                 );
 
-                let swap_amount = &new_total_cap - &previous_total_cap;
-                GlobalOpCheckpoint::ModifyTotalDelegationCap(ModifyTotalDelegationCapData {
-                    new_delegation_cap: new_total_cap,
-                    remaining_swap_waiting_to_active: swap_amount,
-                    remaining_swap_active_to_def_p: BigUint::zero(),
-                    remaining_swap_unstaked_to_def_p: BigUint::zero(),
-                    step: ModifyDelegationCapStep::ComputeAllRewards(ComputeAllRewardsData::new(
-                        self.get_total_cumulated_rewards(),
-                    )),
-                })
+                let swap_amount = new_total_cap - &previous_total_cap;
+                (swap_amount, BigUint::zero(), BigUint::zero())
             }
             Ordering::Less => {
                 // cap decreases
-                let swap_amount = &previous_total_cap - &new_total_cap;
+                let swap_amount = &previous_total_cap - new_total_cap;
                 require!(
                     swap_amount <= self.total_unprotected(),
                     "not enough funds in contract to pay those who are forced unstaked"
                 );
 
-                let swap_unstaked_to_def_p: BigUint;
-                let swap_active_to_def_p: BigUint;
                 if total_unstaked >= swap_amount {
                     // only unstaked -> deferred payment will happen
-                    swap_active_to_def_p = BigUint::zero();
-                    swap_unstaked_to_def_p = swap_amount;
+                    (BigUint::zero(), swap_amount, BigUint::zero())
                 } else {
                     // first unstaked -> deferred payment happens, then active -> deferred payment
-                    swap_active_to_def_p = &swap_amount - &total_unstaked;
-                    swap_unstaked_to_def_p = total_unstaked;
+                    let swap_active_to_def_p = &swap_amount - &total_unstaked;
+                    (BigUint::zero(), total_unstaked, swap_active_to_def_p)
                 }
-
-                GlobalOpCheckpoint::ModifyTotalDelegationCap(ModifyTotalDelegationCapData {
-                    new_delegation_cap: new_total_cap,
-                    remaining_swap_waiting_to_active: BigUint::zero(),
-                    remaining_swap_active_to_def_p: swap_active_to_def_p,
-                    remaining_swap_unstaked_to_def_p: swap_unstaked_to_def_p,
-                    step: ModifyDelegationCapStep::ComputeAllRewards(ComputeAllRewardsData::new(
-                        self.get_total_cumulated_rewards(),
-                    )),
-                })
             }
         };
 
+        Some(amounts)
+    }
+
+    /// Total delegation cap can be modified by owner only.
+    /// It will recalculate and set the checkpoint for all the delegators
+    #[only_owner]
+    #[endpoint(modifyTotalDelegationCap)]
+    fn modify_total_delegation_cap(&self, new_total_cap: BigUint) -> OperationCompletionStatus {
+        self.require_operational();
+        self.require_not_sunset();
+        require!(
+            !self.is_global_op_in_progress(),
+            "cannot modify total delegation cap when last is in progress"
+        );
+
+        self.start_total_delegation_cap_change(new_total_cap)
+    }
+
+    /// If the total delegation cap was not filled by the bootstrap deadline,
+    /// the owner can lower it to what was raised, which ends bootstrap mode.
+    #[only_owner]
+    #[endpoint(lowerDelegationCapToRaised)]
+    fn lower_delegation_cap_to_raised(&self) -> OperationCompletionStatus {
+        self.require_operational();
+        self.require_not_sunset();
+        self.require_bootstrap_deadline_passed();
+        require!(
+            !self.is_global_op_in_progress(),
+            "cannot modify total delegation cap when last is in progress"
+        );
+
+        let raised = self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Waiting)
+            + self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Active)
+            + self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::UnStaked);
+        self.start_total_delegation_cap_change(raised)
+    }
+
+    /// Starts the global operation that moves the stake around for the new cap.
+    /// Callers need to make sure that no other global operation is in progress.
+    fn start_total_delegation_cap_change(
+        &self,
+        new_total_cap: BigUint,
+    ) -> OperationCompletionStatus {
+        let (swap_waiting_to_active, swap_unstaked_to_def_p, swap_active_to_def_p) =
+            match self.delegation_cap_change_swap_amounts(&new_total_cap) {
+                Some(swap_amounts) => swap_amounts,
+                None => {
+                    // still in bootstrap mode, only the cap changes
+                    self.set_total_delegation_cap(new_total_cap);
+                    return OperationCompletionStatus::Completed;
+                }
+            };
+
+        if self.is_bootstrap_mode() {
+            self.set_bootstrap_mode(false);
+        }
+
+        if swap_waiting_to_active == 0 && swap_unstaked_to_def_p == 0 && swap_active_to_def_p == 0 {
+            // no funds move, but when leaving bootstrap mode the cap still drops to what was raised
+            self.set_total_delegation_cap(new_total_cap);
+            return OperationCompletionStatus::Completed;
+        }
+
+        let orc = GlobalOpCheckpoint::ModifyTotalDelegationCap(ModifyTotalDelegationCapData {
+            new_delegation_cap: new_total_cap,
+            remaining_swap_waiting_to_active: swap_waiting_to_active,
+            remaining_swap_active_to_def_p: swap_active_to_def_p,
+            remaining_swap_unstaked_to_def_p: swap_unstaked_to_def_p,
+            step: ModifyDelegationCapStep::ComputeAllRewards(ComputeAllRewardsData::new(
+                self.get_total_cumulated_rewards(),
+            )),
+        });
+
         self.continue_global_operation(orc)
     }
 
+    /// Selects how Active stake is forced out when the total delegation cap decreases:
+    /// - `LastInFirstOut` (default): the most recently activated stake goes first,
+    /// - `ProRata`: all delegators lose the same proportion of their Active stake.
+    #[only_owner]
+    #[endpoint(setForcedUnStakeMode)]
+    fn set_forced_unstake_mode_endpoint(&self, mode: ForcedUnStakeMode) {
+        require!(
+            !self.is_global_op_in_progress(),
+            "global checkpoint is in progress"
+        );
+
+        self.forced_unstake_mode().set(mode);
+    }
+
+    /// Selects how Waiting stake is activated when the total delegation cap increases:
+    /// - `FirstInFirstOut` (default): the oldest Waiting stake is activated first,
+    /// - `ProRata`: all Waiting stake is activated in the same proportion,
+    /// - `CappedPerUser`: each user gets at most `max_per_user` activated in one go.
+    ///
+    /// Waiting stake replacing stake unstaked by users is always activated first-in-first-out,
+    /// so that stake & unstake only ever touch a bounded part of the waiting list.
+    #[only_owner]
+    #[endpoint(setWaitingActivationMode)]
+    fn set_waiting_activation_mode_endpoint(&self, mode: WaitingActivationMode<Self::Api>) {
+        require!(
+            !self.is_global_op_in_progress(),
+            "global checkpoint is in progress"
+        );
+
+        self.waiting_activation_mode().set(mode);
+    }
+
     /// The stake per node can be changed by the owner.
     /// It does not get set in the constructor, so the owner has to manually set it after the contract is deployed.
     #[endpoint(setServiceFee)]
@@ -1,4 +1,7 @@
-use multiversx_sc::{api::ManagedTypeApi, types::BigUint};
+use multiversx_sc::{
+    api::ManagedTypeApi,
+    types::{BigUint, ManagedVec},
+};
 
 multiversx_sc::derive_imports!();
 
@@ -12,6 +15,7 @@ pub enum GlobalOpCheckpoint<M: ManagedTypeApi> {
         new_service_fee: BigUint<M>,
         compute_rewards_data: ComputeAllRewardsData<M>,
     },
+    Sunset(SunsetStep<M>),
 }
 
 impl<M: ManagedTypeApi> GlobalOpCheckpoint<M> {
@@ -49,6 +53,40 @@ pub enum ModifyDelegationCapStep<M: ManagedTypeApi> {
     SwapWaitingToActive,
     SwapUnstakedToDeferredPayment,
     SwapActiveToDeferredPayment,
+    SwapActiveToDeferredPaymentProRata(ProRataSwapData<M>),
+    SwapWaitingToActiveProRata(ProRataSwapData<M>),
+    SwapWaitingToActiveCappedPerUser(CappedPerUserSwapData<M>),
+}
+
+/// Models the steps of converting all remaining stake to deferred payment when the contract is sunset.
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub enum SunsetStep<M: ManagedTypeApi> {
+    ComputeAllRewards(ComputeAllRewardsData<M>),
+    SwapWaitingToDeferredPayment,
+    SwapUnstakedToDeferredPayment,
+    SwapActiveToDeferredPayment,
+}
+
+/// Models the interrupted state of a pro-rata swap.
+/// Every fund item in the source list gets converted in proportion `total_swap / total_source`,
+/// with both totals fixed when the swap starts.
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub struct ProRataSwapData<M: ManagedTypeApi> {
+    pub total_swap: BigUint<M>,
+    pub total_source: BigUint<M>,
+    pub current_id: usize,
+}
+
+/// Models the interrupted state of a swap capped per user.
+/// The users are fixed when the swap starts, and each of them is processed once.
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Debug)]
+pub struct CappedPerUserSwapData<M: ManagedTypeApi> {
+    pub max_per_user: BigUint<M>,
+    pub user_ids: ManagedVec<M, usize>,
+    pub next_index: usize,
 }
 
 /// Models the interrupted state of compute_all_rewards.
@@ -11,8 +11,12 @@ pub trait RewardEndpointsModule:
     + user_fund_storage::user_data::UserDataModule
     + user_fund_storage::fund_module::FundModule
     + user_fund_storage::fund_view_module::FundViewModule
+    + user_fund_storage::fund_transf_module::FundTransformationsModule
+    + crate::user_stake_state::UserStakeStateModule
     + multiversx_sc_modules::features::FeaturesModule
     + multiversx_sc_modules::pause::PauseModule
+    + crate::emergency_mode_state::EmergencyModeStateModule
+    + crate::emergency_mode::EmergencyModeModule
 {
     /// Retrieve those rewards to which the caller is entitled.
     /// Will send:
@@ -20,7 +24,7 @@ pub trait RewardEndpointsModule:
     /// - rewards that were previously computed but not sent
     #[endpoint(claimRewards)]
     fn claim_rewards(&self) {
-        require!(self.not_paused(), "contract paused");
+        self.require_not_paused_or_emergency();
         self.check_feature_on(b"claimRewards", true);
 
         let caller = self.blockchain().get_caller();
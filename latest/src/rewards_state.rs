@@ -74,7 +74,15 @@ pub trait RewardStateModule:
     /// so te rounding error is not in the result.
     /// This is deliberate, to avoid a very subtle rounding error edge case.
     fn split_service_reward(&self, tot_rewards: &BigUint) -> (BigUint, BigUint) {
-        let service_fee = &self.get_service_fee();
+        self.split_service_reward_with_fee(tot_rewards, &self.get_service_fee())
+    }
+
+    /// Same as `split_service_reward`, but with an arbitrary service fee.
+    fn split_service_reward_with_fee(
+        &self,
+        tot_rewards: &BigUint,
+        service_fee: &BigUint,
+    ) -> (BigUint, BigUint) {
         let perc_denominator = &BigUint::from(PERCENTAGE_DENOMINATOR);
 
         // part of the rewards that goes to the owner
@@ -100,17 +108,36 @@ pub trait RewardStateModule:
             return user_data; // nothing happened since the last claim
         }
 
+        user_data.unclaimed_rewards +=
+            self.compute_user_new_rewards(user_id, &tot_new_rewards, &self.get_service_fee());
+
+        // update user data checkpoint
+        user_data.reward_checkpoint = tot_cumul_rewards;
+
+        user_data
+    }
+
+    /// Yields the share of a user out of `tot_new_rewards`, given the service fee.
+    /// The share is based on the current Active stake and total delegation cap.
+    /// Does not update storage.
+    fn compute_user_new_rewards(
+        &self,
+        user_id: NonZeroUsize,
+        tot_new_rewards: &BigUint,
+        service_fee: &BigUint,
+    ) -> BigUint {
         // the owner is entitled to: tot_new_rewards * service_fee / NODE_DENOMINATOR
         // delegators are entitled to: tot_new_rewards * (1 - service_fee / NODE_DENOMINATOR)
         let (service_rewards, total_delegators_rewards) =
-            self.split_service_reward(&tot_new_rewards);
+            self.split_service_reward_with_fee(tot_new_rewards, service_fee);
 
         let delegation_cap = self.get_total_delegation_cap();
+        let mut new_rewards = BigUint::zero();
 
         // update node rewards, if applicable
         if user_id == OWNER_USER_ID {
             // the owner gets the service fee
-            user_data.unclaimed_rewards += &service_rewards;
+            new_rewards += &service_rewards;
 
             // the owner gets the rewards for the missing active (unstaked) stake
             let tot_stake_active =
@@ -120,7 +147,7 @@ pub trait RewardStateModule:
                 let mut owner_unstaked_rewards = total_delegators_rewards.clone();
                 owner_unstaked_rewards *= &missing_stake;
                 owner_unstaked_rewards /= &delegation_cap;
-                user_data.unclaimed_rewards += &owner_unstaked_rewards;
+                new_rewards += &owner_unstaked_rewards;
             }
         }
 
@@ -132,13 +159,10 @@ pub trait RewardStateModule:
             let mut delegator_new_rewards = total_delegators_rewards;
             delegator_new_rewards *= &u_stake_active;
             delegator_new_rewards /= &delegation_cap;
-            user_data.unclaimed_rewards += &delegator_new_rewards;
+            new_rewards += &delegator_new_rewards;
         }
 
-        // update user data checkpoint
-        user_data.reward_checkpoint = tot_cumul_rewards;
-
-        user_data
+        new_rewards
     }
 
     /// Convenience method, brings user rewards up to date for one user.
@@ -192,6 +216,13 @@ pub trait RewardStateModule:
 
     #[view(getTotalUnProtected)]
     fn total_unprotected(&self) -> BigUint {
+        self.try_total_unprotected()
+            .unwrap_or_else(|| sc_panic!("total unprotected underflow"))
+    }
+
+    /// Yields `None` if the balance does not cover the funds reserved in the contract,
+    /// which should never happen.
+    fn try_total_unprotected(&self) -> Option<BigUint> {
         let sent_rewards = self.get_sent_rewards();
         let total_rewards = self.get_total_cumulated_rewards();
         let total_waiting = self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Waiting);
@@ -200,14 +231,16 @@ pub trait RewardStateModule:
         let total_withdraw =
             self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::WithdrawOnly);
 
-        let mut unprotected = self
+        #[allow(clippy::needless_borrows_for_generic_args)]
+        let available = self
             .blockchain()
             .get_sc_balance(&EgldOrEsdtTokenIdentifier::egld(), 0)
             + sent_rewards;
-        unprotected -= total_rewards;
-        unprotected -= total_waiting;
-        unprotected -= total_deferred;
-        unprotected -= total_withdraw;
-        unprotected
+        let reserved = total_rewards + total_waiting + total_deferred + total_withdraw;
+        if available < reserved {
+            return None;
+        }
+
+        Some(available - reserved)
     }
 }
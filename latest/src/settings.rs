@@ -1,6 +1,7 @@
 use core::num::NonZeroUsize;
 
 multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
 
 /// Indicates how we express the percentage of rewards that go to the node.
 /// Since we cannot have floating point numbers, we use fixed point with this denominator.
@@ -11,6 +12,32 @@ pub const PERCENTAGE_DENOMINATOR: usize = 10000;
 /// This can also count as a delegator (if the owner adds stake into the contract) or not.
 pub const OWNER_USER_ID: NonZeroUsize = NonZeroUsize::new(1).unwrap();
 
+/// Decides whose Active stake gets converted to deferred payment when the total delegation cap decreases.
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone, Copy, Debug)]
+pub enum ForcedUnStakeMode {
+    /// The most recently activated stake is forced out first.
+    LastInFirstOut,
+
+    /// All delegators lose the same proportion of their Active stake.
+    ProRata,
+}
+
+/// Decides whose Waiting stake gets activated when there is room for more Active stake.
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone, Debug)]
+pub enum WaitingActivationMode<M: ManagedTypeApi> {
+    /// The oldest Waiting stake is activated first.
+    FirstInFirstOut,
+
+    /// All Waiting stake is activated in the same proportion.
+    ProRata,
+
+    /// Each user gets at most `max_per_user` activated, in the order in which they joined the waiting list.
+    /// Whatever cannot be activated this way is activated first-in-first-out.
+    CappedPerUser { max_per_user: BigUint<M> },
+}
+
 /// The module deals with initializaton and the global contract settings.
 ///
 #[multiversx_sc::derive::module]
@@ -47,6 +74,41 @@ pub trait SettingsModule {
     #[storage_set("bootstrap_mode")]
     fn set_bootstrap_mode(&self, bootstrap_mode: bool);
 
+    /// Epoch from which delegators can reclaim their stake if the total delegation cap was not filled.
+    /// 0 means no deadline.
+    #[view(getBootstrapDeadline)]
+    #[storage_mapper("bootstrap_deadline")]
+    fn bootstrap_deadline(&self) -> SingleValueMapper<u64>;
+
+    /// Only possible in bootstrap mode.
+    /// Once set, the deadline can only be brought forward, so that delegators can rely on it.
+    #[only_owner]
+    #[endpoint(setBootstrapDeadline)]
+    fn set_bootstrap_deadline_endpoint(&self, deadline_epoch: u64) {
+        require!(self.is_bootstrap_mode(), "not in bootstrap mode");
+
+        let current_deadline = self.bootstrap_deadline().get();
+        require!(
+            current_deadline == 0 || (deadline_epoch > 0 && deadline_epoch <= current_deadline),
+            "cannot postpone the bootstrap deadline"
+        );
+
+        self.bootstrap_deadline().set(deadline_epoch);
+    }
+
+    #[view(isBootstrapDeadlinePassed)]
+    fn is_bootstrap_deadline_passed(&self) -> bool {
+        let deadline = self.bootstrap_deadline().get();
+        self.is_bootstrap_mode() && deadline > 0 && self.blockchain().get_block_epoch() >= deadline
+    }
+
+    fn require_bootstrap_deadline_passed(&self) {
+        require!(
+            self.is_bootstrap_deadline_passed(),
+            "bootstrap deadline not passed"
+        );
+    }
+
     /// The minimum proportion of stake that has to be provided by the owner.
     /// 10000 = 100%.
     #[view(getOwnerMinStakeShare)]
@@ -93,4 +155,14 @@ pub trait SettingsModule {
     fn set_minimum_stake_endpoint(&self, minimum_stake: BigUint) {
         self.set_minimum_stake(&minimum_stake);
     }
+
+    /// How Active stake is forced out when the total delegation cap decreases.
+    #[view(getForcedUnStakeMode)]
+    #[storage_mapper("forced_unstake_mode")]
+    fn forced_unstake_mode(&self) -> SingleValueMapper<ForcedUnStakeMode>;
+
+    /// How Waiting stake is activated when there is room for more Active stake.
+    #[view(getWaitingActivationMode)]
+    #[storage_mapper("waiting_activation_mode")]
+    fn waiting_activation_mode(&self) -> SingleValueMapper<WaitingActivationMode<Self::Api>>;
 }
@@ -0,0 +1,444 @@
+use core::num::NonZeroUsize;
+
+use crate::settings::{ForcedUnStakeMode, WaitingActivationMode, PERCENTAGE_DENOMINATOR};
+use user_fund_storage::fund_module::SwapDirection;
+use user_fund_storage::fund_view_module::USER_STAKE_TOTALS_ID;
+use user_fund_storage::types::FundType;
+
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+/// An amount of stake attributed to a user.
+/// Used both as an in-memory copy of a fund item and to accumulate how much was moved for each user.
+#[derive(ManagedVecItem, Clone)]
+pub struct UserAmount<M: ManagedTypeApi> {
+    pub user_id: usize,
+    pub amount: BigUint<M>,
+}
+
+/// Read-only views that preview the effects of admin and user operations.
+/// They replay the fund swaps in memory, without writing to storage.
+#[multiversx_sc::derive::module]
+pub trait SimulationModule:
+    crate::rewards_state::RewardStateModule
+    + crate::reset_checkpoint_state::ResetCheckpointStateModule
+    + crate::reset_checkpoint_endpoints::ResetCheckpointsModule
+    + crate::user_stake_state::UserStakeStateModule
+    + crate::events::EventsModule
+    + crate::governance::GovernanceModule
+    + crate::settings::SettingsModule
+    + multiversx_sc_modules::features::FeaturesModule
+    + user_fund_storage::user_data::UserDataModule
+    + user_fund_storage::fund_module::FundModule
+    + user_fund_storage::fund_view_module::FundViewModule
+    + user_fund_storage::fund_transf_module::FundTransformationsModule
+    + crate::sunset_state::SunsetStateModule
+    + multiversx_sc_modules::pause::PauseModule
+    + crate::emergency_mode_state::EmergencyModeStateModule
+{
+    /// Previews `modifyTotalDelegationCap`.
+    /// For each affected user it yields: the user address, and the amounts that would be moved
+    /// Waiting -> Active, UnStaked -> DeferredPayment and Active -> DeferredPayment.
+    /// Takes the current waiting activation mode and forced unstake mode into account.
+    #[view(simulateModifyTotalDelegationCap)]
+    fn simulate_modify_total_delegation_cap(
+        &self,
+        new_total_cap: BigUint,
+    ) -> MultiValueEncoded<MultiValue4<ManagedAddress, BigUint, BigUint, BigUint>> {
+        require!(
+            !self.is_global_op_in_progress(),
+            "cannot modify total delegation cap when last is in progress"
+        );
+
+        let mut result = MultiValueEncoded::new();
+        let (swap_waiting_to_active, swap_unstaked_to_def_p, swap_active_to_def_p) =
+            match self.delegation_cap_change_swap_amounts(&new_total_cap) {
+                Some(swap_amounts) => swap_amounts,
+                None => return result,
+            };
+
+        let mut waiting_to_active = ManagedVec::new();
+        if swap_waiting_to_active > 0 {
+            self.simulate_swap_waiting_to_active(&swap_waiting_to_active, &mut waiting_to_active);
+        }
+
+        let mut unstaked_to_def_p = ManagedVec::new();
+        if swap_unstaked_to_def_p > 0 {
+            let mut unstaked_funds = self.load_simulated_funds(FundType::UnStaked);
+            let mut remaining = swap_unstaked_to_def_p;
+            self.simulate_swap_in_order(
+                &mut unstaked_funds,
+                &mut remaining,
+                SwapDirection::Forwards,
+                &mut unstaked_to_def_p,
+            );
+        }
+
+        let mut active_to_def_p = ManagedVec::new();
+        if swap_active_to_def_p > 0 {
+            self.simulate_swap_active_to_deferred_payment(
+                &swap_active_to_def_p,
+                &mut active_to_def_p,
+            );
+        }
+
+        for user_id in 1..=self.get_num_users() {
+            let from_waiting = get_user_amount(&waiting_to_active, user_id);
+            let from_unstaked = get_user_amount(&unstaked_to_def_p, user_id);
+            let from_active = get_user_amount(&active_to_def_p, user_id);
+            if from_waiting > 0 || from_unstaked > 0 || from_active > 0 {
+                result.push(
+                    (
+                        self.get_user_address(user_id),
+                        from_waiting,
+                        from_unstaked,
+                        from_active,
+                    )
+                        .into(),
+                );
+            }
+        }
+
+        result
+    }
+
+    /// Previews `unStake` for a user.
+    /// Yields the stake of the user after the operation, by type:
+    /// withdraw only, waiting, active, unstaked, deferred payment,
+    /// followed by the rewards the user can claim.
+    /// Waiting stake replacing unstaked stake is activated first-in-first-out in every waiting activation mode,
+    /// so the swap to deferred payment shows up right away, as it does in `unStake`.
+    #[view(simulateUnstake)]
+    fn simulate_unstake(
+        &self,
+        user_address: ManagedAddress,
+        amount: BigUint,
+    ) -> MultiValue6<BigUint, BigUint, BigUint, BigUint, BigUint, BigUint> {
+        require!(
+            !self.is_global_op_in_progress(),
+            "unstaking is temporarily paused as checkpoint is reset"
+        );
+
+        let user_id = NonZeroUsize::new(self.get_user_id(&user_address))
+            .unwrap_or_else(|| sc_panic!("only delegators can unstake"));
+        self.validate_unstake_amount(user_id.get(), &amount);
+        self.require_unstake_keeps_locked_voting_power(&user_address, &amount);
+
+        // rewards are brought up to date before any Active stake is converted,
+        // so the operation itself does not change them
+        let claimable_rewards = self.load_updated_user_rewards(user_id).unclaimed_rewards;
+
+        let mut withdraw_only = self.get_user_stake_of_type(user_id.get(), FundType::WithdrawOnly);
+        let mut waiting = self.get_user_stake_of_type(user_id.get(), FundType::Waiting);
+        let mut active = self.get_user_stake_of_type(user_id.get(), FundType::Active);
+        let mut unstaked = self.get_user_stake_of_type(user_id.get(), FundType::UnStaked);
+        let mut deferred_payment =
+            self.get_user_stake_of_type(user_id.get(), FundType::DeferredPayment);
+
+        // first the waiting stake is removed
+        let from_waiting = core::cmp::min(&amount, &waiting).clone();
+        waiting -= &from_waiting;
+        withdraw_only += &from_waiting;
+
+        let remaining = &amount - &from_waiting;
+        if remaining > 0 {
+            // then Active -> UnStaked
+            active -= &remaining;
+            unstaked += &remaining;
+
+            // then waiting stake from the other users replaces the unstaked stake, first-in-first-out
+            if self.is_bootstrap_mode() {
+                deferred_payment += &unstaked;
+                unstaked = BigUint::zero();
+            } else {
+                let total_waiting = self
+                    .get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Waiting)
+                    - &from_waiting;
+                let total_unstaked = self
+                    .get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::UnStaked)
+                    + &remaining;
+                let mut swappable = core::cmp::min(&total_waiting, &total_unstaked).clone();
+                if swappable > 0 {
+                    // the new UnStaked fund item goes at the end of the list
+                    let mut unstaked_funds = self.load_simulated_funds(FundType::UnStaked);
+                    unstaked_funds.push(UserAmount {
+                        user_id: user_id.get(),
+                        amount: remaining,
+                    });
+                    let mut unstaked_to_def_p = ManagedVec::new();
+                    self.simulate_swap_in_order(
+                        &mut unstaked_funds,
+                        &mut swappable,
+                        SwapDirection::Forwards,
+                        &mut unstaked_to_def_p,
+                    );
+
+                    let swapped = get_user_amount(&unstaked_to_def_p, user_id.get());
+                    unstaked -= &swapped;
+                    deferred_payment += &swapped;
+                }
+            }
+        }
+
+        (
+            withdraw_only,
+            waiting,
+            active,
+            unstaked,
+            deferred_payment,
+            claimable_rewards,
+        )
+            .into()
+    }
+
+    /// Previews how a new service fee changes the way rewards are split.
+    /// For each user entitled to rewards it yields: the user address,
+    /// the user share with the current service fee and the user share with the new service fee.
+    /// Shares are computed out of `reward_amount`, based on the current Active stake.
+    /// If `reward_amount` is missing, all the rewards received so far are used instead.
+    /// Rewards already computed are not affected by a service fee change.
+    #[view(simulateServiceFee)]
+    fn simulate_service_fee(
+        &self,
+        service_fee_per_10000: usize,
+        opt_reward_amount: OptionalValue<BigUint>,
+    ) -> MultiValueEncoded<MultiValue3<ManagedAddress, BigUint, BigUint>> {
+        require!(
+            service_fee_per_10000 <= PERCENTAGE_DENOMINATOR,
+            "service fee out of range"
+        );
+
+        let reward_amount = match opt_reward_amount {
+            OptionalValue::Some(reward_amount) => reward_amount,
+            OptionalValue::None => self.get_total_cumulated_rewards(),
+        };
+        let current_service_fee = self.get_service_fee();
+        let new_service_fee = BigUint::from(service_fee_per_10000);
+
+        let mut result = MultiValueEncoded::new();
+        if reward_amount == 0 {
+            return result;
+        }
+
+        for user_id in NonZeroUsizeIterator::from_1_to_n(self.get_num_users()) {
+            let current_rewards =
+                self.compute_user_new_rewards(user_id, &reward_amount, &current_service_fee);
+            let new_rewards =
+                self.compute_user_new_rewards(user_id, &reward_amount, &new_service_fee);
+            if current_rewards > 0 || new_rewards > 0 {
+                result.push(
+                    (
+                        self.get_user_address(user_id.get()),
+                        current_rewards,
+                        new_rewards,
+                    )
+                        .into(),
+                );
+            }
+        }
+
+        result
+    }
+
+    /// Copies all fund items of a type in memory, in list order.
+    fn load_simulated_funds(&self, fund_type: FundType) -> ManagedVec<UserAmount<Self::Api>> {
+        let mut funds = ManagedVec::new();
+        self.foreach_fund_by_type(fund_type, SwapDirection::Forwards, |fund_item| {
+            funds.push(UserAmount {
+                user_id: fund_item.user_id,
+                amount: fund_item.balance,
+            });
+        });
+        funds
+    }
+
+    /// Replays `swap_waiting_to_active` and its variants, according to the waiting activation mode.
+    fn simulate_swap_waiting_to_active(
+        &self,
+        amount: &BigUint,
+        moved: &mut ManagedVec<UserAmount<Self::Api>>,
+    ) {
+        let mut waiting_funds = self.load_simulated_funds(FundType::Waiting);
+        let mut remaining = amount.clone();
+        match self.waiting_activation_mode().get() {
+            WaitingActivationMode::FirstInFirstOut => {}
+            WaitingActivationMode::ProRata => {
+                let total_waiting =
+                    self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Waiting);
+                simulate_swap_pro_rata(
+                    &mut waiting_funds,
+                    &mut remaining,
+                    amount,
+                    &total_waiting,
+                    moved,
+                );
+            }
+            WaitingActivationMode::CappedPerUser { max_per_user } => {
+                simulate_swap_capped_per_user(
+                    &mut waiting_funds,
+                    &mut remaining,
+                    &max_per_user,
+                    moved,
+                );
+            }
+        }
+
+        // whatever is left is activated first-in-first-out
+        self.simulate_swap_in_order(
+            &mut waiting_funds,
+            &mut remaining,
+            SwapDirection::Forwards,
+            moved,
+        );
+    }
+
+    /// Replays `swap_active_to_deferred_payment` and its variants, according to the forced unstake mode.
+    fn simulate_swap_active_to_deferred_payment(
+        &self,
+        amount: &BigUint,
+        moved: &mut ManagedVec<UserAmount<Self::Api>>,
+    ) {
+        let mut active_funds = self.load_simulated_funds(FundType::Active);
+        let mut remaining = amount.clone();
+        if self.forced_unstake_mode().get() == ForcedUnStakeMode::ProRata {
+            let total_active = self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Active);
+            simulate_swap_pro_rata(
+                &mut active_funds,
+                &mut remaining,
+                amount,
+                &total_active,
+                moved,
+            );
+        }
+
+        // whatever is left is forced out last-in-first-out
+        self.simulate_swap_in_order(
+            &mut active_funds,
+            &mut remaining,
+            SwapDirection::Backwards,
+            moved,
+        );
+    }
+
+    /// Takes from the fund items one by one, in the given direction, until `remaining` is exhausted.
+    fn simulate_swap_in_order(
+        &self,
+        funds: &mut ManagedVec<UserAmount<Self::Api>>,
+        remaining: &mut BigUint,
+        direction: SwapDirection,
+        moved: &mut ManagedVec<UserAmount<Self::Api>>,
+    ) {
+        let len = funds.len();
+        for i in 0..len {
+            if *remaining == 0 {
+                return;
+            }
+
+            let index = match direction {
+                SwapDirection::Forwards => i,
+                SwapDirection::Backwards => len - 1 - i,
+            };
+            let take = core::cmp::min(&funds.get(index).amount, &*remaining).clone();
+            take_from_fund(funds, index, &take, moved);
+            *remaining -= &take;
+        }
+    }
+}
+
+/// Same rounding as `swap_pro_rata`: each fund item gives `balance * total_swap / total_source`, rounded down.
+fn simulate_swap_pro_rata<M: ManagedTypeApi>(
+    funds: &mut ManagedVec<M, UserAmount<M>>,
+    remaining: &mut BigUint<M>,
+    total_swap: &BigUint<M>,
+    total_source: &BigUint<M>,
+    moved: &mut ManagedVec<M, UserAmount<M>>,
+) {
+    for index in 0..funds.len() {
+        let mut share = &funds.get(index).amount * total_swap / total_source;
+        if share > *remaining {
+            share = remaining.clone();
+        }
+        take_from_fund(funds, index, &share, moved);
+        *remaining -= &share;
+    }
+}
+
+/// Same as `swap_waiting_to_active_capped_per_user`: users in the order of their oldest fund item,
+/// each of them giving no more than `max_per_user`.
+fn simulate_swap_capped_per_user<M: ManagedTypeApi>(
+    funds: &mut ManagedVec<M, UserAmount<M>>,
+    remaining: &mut BigUint<M>,
+    max_per_user: &BigUint<M>,
+    moved: &mut ManagedVec<M, UserAmount<M>>,
+) {
+    let mut processed_user_ids = ManagedVec::<M, usize>::new();
+    for index in 0..funds.len() {
+        if *remaining == 0 {
+            return;
+        }
+
+        let user_id = funds.get(index).user_id;
+        if processed_user_ids.contains(&user_id) {
+            continue;
+        }
+        processed_user_ids.push(user_id);
+
+        let mut user_max = core::cmp::min(&*remaining, max_per_user).clone();
+        for user_index in index..funds.len() {
+            if user_max == 0 {
+                break;
+            }
+
+            let fund = funds.get(user_index).clone();
+            if fund.user_id == user_id {
+                let take = core::cmp::min(&fund.amount, &user_max).clone();
+                take_from_fund(funds, user_index, &take, moved);
+                user_max -= &take;
+                *remaining -= &take;
+            }
+        }
+    }
+}
+
+/// Decreases the fund item at `index` and records the amount as moved for its user.
+fn take_from_fund<M: ManagedTypeApi>(
+    funds: &mut ManagedVec<M, UserAmount<M>>,
+    index: usize,
+    amount: &BigUint<M>,
+    moved: &mut ManagedVec<M, UserAmount<M>>,
+) {
+    if *amount == 0 {
+        return;
+    }
+
+    let mut fund = funds.get(index).clone();
+    fund.amount -= amount;
+    let user_id = fund.user_id;
+    let _ = funds.set(index, fund);
+
+    match moved
+        .iter()
+        .position(|user_amount| user_amount.user_id == user_id)
+    {
+        Some(position) => {
+            let mut user_amount = moved.get(position).clone();
+            user_amount.amount += amount;
+            let _ = moved.set(position, user_amount);
+        }
+        None => moved.push(UserAmount {
+            user_id,
+            amount: amount.clone(),
+        }),
+    }
+}
+
+fn get_user_amount<M: ManagedTypeApi>(
+    user_amounts: &ManagedVec<M, UserAmount<M>>,
+    user_id: usize,
+) -> BigUint<M> {
+    user_amounts
+        .iter()
+        .find(|user_amount| user_amount.user_id == user_id)
+        .map(|user_amount| user_amount.amount.clone())
+        .unwrap_or_default()
+}
@@ -0,0 +1,175 @@
+use crate::reset_checkpoint_types::{ComputeAllRewardsData, GlobalOpCheckpoint, SunsetStep};
+use crate::sunset_types::{SunsetPhase, SunsetStatus};
+use node_storage::{node_config::MAX_NODES_PER_OPERATION, types::NodeState};
+use user_fund_storage::fund_view_module::USER_STAKE_TOTALS_ID;
+use user_fund_storage::types::FundType;
+
+multiversx_sc::imports!();
+
+/// Winds down the contract: unstakes and unbonds all nodes, converts all stake to deferred payment
+/// and lets every user withdraw.
+///
+/// The owner starts the sunset, after that anyone can move it forward with `continueSunset`,
+/// so that delegators do not depend on the owner to get their funds out.
+/// New stake, node staking and cap changes are no longer possible once the sunset started.
+#[multiversx_sc::derive::module]
+pub trait SunsetModule:
+    node_storage::node_config::NodeConfigModule
+    + user_fund_storage::user_data::UserDataModule
+    + user_fund_storage::fund_module::FundModule
+    + user_fund_storage::fund_view_module::FundViewModule
+    + user_fund_storage::fund_transf_module::FundTransformationsModule
+    + crate::settings::SettingsModule
+    + crate::reset_checkpoint_state::ResetCheckpointStateModule
+    + crate::reset_checkpoint_endpoints::ResetCheckpointsModule
+    + crate::rewards_state::RewardStateModule
+    + crate::user_stake_state::UserStakeStateModule
+    + crate::events::EventsModule
+    + crate::node_jail_history::NodeJailHistoryModule
+    + crate::node_activation::NodeActivationModule
+    + crate::sunset_state::SunsetStateModule
+    + multiversx_sc_modules::features::FeaturesModule
+    + multiversx_sc_modules::pause::PauseModule
+    + crate::emergency_mode_state::EmergencyModeStateModule
+{
+    /// Irreversibly starts winding down the contract, by unstaking the first batch of Active nodes.
+    /// Neither the pause nor emergency mode hold back the sunset.
+    #[only_owner]
+    #[endpoint(startSunset)]
+    fn start_sunset(&self) {
+        self.require_not_sunset();
+        require!(
+            !self.is_global_op_in_progress(),
+            "global checkpoint is in progress"
+        );
+
+        self.set_sunset_phase(SunsetPhase::UnStakingNodes);
+        self.unstake_nodes_for_sunset();
+    }
+
+    /// Moves the sunset forward, as far as currently possible:
+    /// - unstakes the next batch of Active nodes, then the top-up, then
+    /// - unbonds the nodes whose unbond period passed, then the top-up, then
+    /// - converts all Waiting, Active and UnStaked stake to DeferredPayment.
+    ///
+    /// Node operations end the transaction, and `continueSunset` has to be called again once their callback arrived.
+    /// Converting the stake is a global operation, that can also be continued with `continueGlobalOperation`.
+    ///
+    /// If stake was lost, for instance to a jail fine, the balance does not cover all the stake.
+    /// Users are then paid in full in the order they `unBond`, until the balance runs out.
+    #[endpoint(continueSunset)]
+    fn continue_sunset(&self) -> OperationCompletionStatus {
+        match self.sunset_phase().get() {
+            SunsetPhase::NotStarted => sc_panic!("sunset not started"),
+            SunsetPhase::UnStakingNodes => {
+                self.unstake_nodes_for_sunset();
+                self.unbond_nodes_for_sunset()
+            }
+            SunsetPhase::UnBondingNodes => self.unbond_nodes_for_sunset(),
+            SunsetPhase::ConvertingStake => {
+                let orc = self.global_op_checkpoint().get();
+                self.continue_global_operation(orc)
+            }
+            SunsetPhase::Withdrawing => sc_panic!("sunset already complete"),
+        }
+    }
+
+    /// Unstakes the next batch of Active nodes, together with their tokens, which ends the transaction.
+    /// Moves on to unbonding once none are left to unstake, starting with unstaking the top-up.
+    fn unstake_nodes_for_sunset(&self) {
+        let node_ids = self.first_nodes_in_state(NodeState::Active, MAX_NODES_PER_OPERATION);
+        if !node_ids.is_empty() {
+            let bls_keys = self.bls_keys_of_nodes(&node_ids);
+            self.perform_unstake_nodes(true, node_ids, bls_keys);
+        }
+
+        require!(
+            self.nodes_by_state(NodeState::PendingActivation.discriminant())
+                .is_empty()
+                && self
+                    .nodes_by_state(NodeState::PendingDeactivation.discriminant())
+                    .is_empty(),
+            "waiting for auction callbacks"
+        );
+
+        self.set_sunset_phase(SunsetPhase::UnBondingNodes);
+        self.unstake_top_up_for_sunset();
+    }
+
+    /// Unstakes all the top-up, which ends the transaction.
+    /// It is retried on the next call if the auction SC rejects it.
+    fn unstake_top_up_for_sunset(&self) {
+        let total_top_up = self.total_top_up().get();
+        if total_top_up > 0 {
+            self.perform_unstake_top_up(total_top_up);
+        }
+    }
+
+    /// Unbonds the nodes in the unbond period, then the unstaked top-up, which ends the transaction.
+    /// The auction SC rejects them while their unbond period did not pass yet, they are retried on the next call.
+    /// Starts converting the stake once everything is unbonded.
+    fn unbond_nodes_for_sunset(&self) -> OperationCompletionStatus {
+        let node_ids = self.first_nodes_in_state(
+            NodeState::UnBondPeriod { started: 0 },
+            MAX_NODES_PER_OPERATION,
+        );
+        if !node_ids.is_empty() {
+            let bls_keys = self.bls_keys_of_nodes(&node_ids);
+            for &node_id in node_ids.iter() {
+                self.prepare_node_for_unbond_if_possible(node_id);
+            }
+            self.perform_unbond(node_ids, bls_keys);
+        }
+
+        require!(
+            self.nodes_by_state(NodeState::PendingUnBond { unbond_started: 0 }.discriminant())
+                .is_empty(),
+            "waiting for auction callbacks"
+        );
+
+        self.unstake_top_up_for_sunset();
+        if !self.unstaked_top_up().is_empty() {
+            self.perform_unbond_top_up();
+        }
+
+        self.set_sunset_phase(SunsetPhase::ConvertingStake);
+        self.continue_global_operation(GlobalOpCheckpoint::Sunset(SunsetStep::ComputeAllRewards(
+            ComputeAllRewardsData::new(self.get_total_cumulated_rewards()),
+        )))
+    }
+
+    #[view(getSunsetStatus)]
+    fn get_sunset_status(&self) -> SunsetStatus<Self::Api> {
+        let num_nodes_in_states = |states: [NodeState; 2]| -> usize {
+            states
+                .iter()
+                .map(|state| self.nodes_by_state(state.discriminant()).len())
+                .sum()
+        };
+        let total_of_types = |fund_types: &[FundType]| -> BigUint {
+            let mut total = BigUint::zero();
+            for &fund_type in fund_types {
+                total += self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, fund_type);
+            }
+            total
+        };
+
+        SunsetStatus {
+            phase: self.sunset_phase().get(),
+            num_nodes_to_unstake: num_nodes_in_states([
+                NodeState::Active,
+                NodeState::PendingDeactivation,
+            ]),
+            num_nodes_to_unbond: num_nodes_in_states([
+                NodeState::UnBondPeriod { started: 0 },
+                NodeState::PendingUnBond { unbond_started: 0 },
+            ]),
+            stake_to_convert: total_of_types(&[
+                FundType::Waiting,
+                FundType::Active,
+                FundType::UnStaked,
+            ]),
+            stake_to_withdraw: total_of_types(&[FundType::DeferredPayment, FundType::WithdrawOnly]),
+        }
+    }
+}
@@ -0,0 +1,23 @@
+use crate::sunset_types::SunsetPhase;
+
+multiversx_sc::imports!();
+
+#[multiversx_sc::derive::module]
+pub trait SunsetStateModule: crate::events::EventsModule {
+    #[storage_mapper("sunset_phase")]
+    fn sunset_phase(&self) -> SingleValueMapper<SunsetPhase>;
+
+    fn set_sunset_phase(&self, phase: SunsetPhase) {
+        self.sunset_phase().set(phase);
+        self.sunset_phase_changed_event(phase);
+    }
+
+    #[view(isSunset)]
+    fn is_sunset(&self) -> bool {
+        self.sunset_phase().get() != SunsetPhase::NotStarted
+    }
+
+    fn require_not_sunset(&self) {
+        require!(!self.is_sunset(), "contract is being sunset");
+    }
+}
@@ -0,0 +1,43 @@
+use multiversx_sc::{api::ManagedTypeApi, types::BigUint};
+
+multiversx_sc::derive_imports!();
+
+/// The phases a contract goes through when it is sunset, in order.
+/// Once started, a sunset cannot be stopped.
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone, Copy, Debug)]
+pub enum SunsetPhase {
+    NotStarted,
+
+    /// All Active nodes are being unstaked, together with their tokens.
+    UnStakingNodes,
+
+    /// Waiting for the unbond period of the nodes to pass, then unbonding them.
+    UnBondingNodes,
+
+    /// All Waiting, Active and UnStaked stake is being converted to DeferredPayment,
+    /// as a global operation.
+    ConvertingStake,
+
+    /// Done. Users can `unBond` all their stake and `claimRewards`.
+    Withdrawing,
+}
+
+/// Progress of the sunset, as yielded by `getSunsetStatus`.
+#[type_abi]
+#[derive(TopEncode, TopDecode, PartialEq, Debug)]
+pub struct SunsetStatus<M: ManagedTypeApi> {
+    pub phase: SunsetPhase,
+
+    /// Nodes that are Active or pending deactivation.
+    pub num_nodes_to_unstake: usize,
+
+    /// Nodes that are in the unbond period or pending unbond.
+    pub num_nodes_to_unbond: usize,
+
+    /// Waiting + Active + UnStaked stake, still to be converted to DeferredPayment.
+    pub stake_to_convert: BigUint<M>,
+
+    /// DeferredPayment + WithdrawOnly stake, not yet withdrawn by the users.
+    pub stake_to_withdraw: BigUint<M>,
+}
@@ -1,6 +1,8 @@
 use crate::settings::OWNER_USER_ID;
+use crate::sunset_types::SunsetPhase;
 use core::num::NonZeroUsize;
-use user_fund_storage::types::FundType;
+use user_fund_storage::fund_module::SwapDirection;
+use user_fund_storage::types::{FundDescription, FundType};
 
 multiversx_sc::imports!();
 
@@ -13,19 +15,24 @@ pub trait UserStakeEndpointsModule:
     + crate::rewards_state::RewardStateModule
     + crate::settings::SettingsModule
     + crate::events::EventsModule
+    + crate::governance::GovernanceModule
     + user_fund_storage::user_data::UserDataModule
     + user_fund_storage::fund_module::FundModule
     + user_fund_storage::fund_view_module::FundViewModule
     + user_fund_storage::fund_transf_module::FundTransformationsModule
     + multiversx_sc_modules::features::FeaturesModule
     + multiversx_sc_modules::pause::PauseModule
+    + crate::emergency_mode_state::EmergencyModeStateModule
+    + crate::emergency_mode::EmergencyModeModule
+    + crate::sunset_state::SunsetStateModule
 {
     /// Delegate stake to the smart contract.
     /// Stake is initially inactive, so does it not produce rewards.
     #[payable("EGLD")]
     #[endpoint(stake)]
     fn stake_endpoint(&self, #[payment] payment: BigUint) {
-        require!(self.not_paused(), "contract paused");
+        self.require_operational();
+        self.require_not_sunset();
 
         require!(
             payment >= self.get_minimum_stake(),
@@ -44,7 +51,7 @@ pub trait UserStakeEndpointsModule:
     /// selected funds will change from active to inactive, but claimable only after unBond period ends
     #[endpoint(unStake)]
     fn unstake_endpoint(&self, amount: BigUint) {
-        require!(self.not_paused(), "contract paused");
+        self.require_operational();
 
         require!(
             !self.is_global_op_in_progress(),
@@ -58,6 +65,9 @@ pub trait UserStakeEndpointsModule:
         // validate that amount does not exceed existing waiting + active stake
         self.validate_unstake_amount(unstake_user_id.get(), &amount);
 
+        // stake that voted on open proposals must stay
+        self.require_unstake_keeps_locked_voting_power(&caller, &amount);
+
         // first try to remove funds from waiting list
         let mut remaining = amount;
         self.swap_user_waiting_to_withdraw_only(unstake_user_id.get(), &mut remaining);
@@ -73,6 +83,7 @@ pub trait UserStakeEndpointsModule:
         // convert Active -> UnStaked
         self.swap_user_active_to_unstaked(unstake_user_id.get(), &mut remaining);
         require!(remaining == 0, "error converting Active to UnStaked");
+        self.record_unstaked_fund_epoch(unstake_user_id.get());
 
         // move funds around
         self.use_waiting_to_replace_unstaked();
@@ -81,6 +92,50 @@ pub trait UserStakeEndpointsModule:
         self.validate_user_minimum_stake(unstake_user_id.get());
     }
 
+    /// If the total delegation cap was not filled by the bootstrap deadline,
+    /// delegators can take back all their Waiting and Active stake.
+    /// It becomes WithdrawOnly right away, and can be withdrawn with `unBond`.
+    /// Active stake that the owner already staked in nodes cannot be reclaimed this way.
+    #[endpoint(reclaimBootstrapStake)]
+    fn reclaim_bootstrap_stake(&self) -> BigUint {
+        self.require_not_paused_or_emergency();
+        self.require_bootstrap_deadline_passed();
+
+        require!(
+            !self.is_global_op_in_progress(),
+            "reclaiming is temporarily paused as checkpoint is reset"
+        );
+
+        let caller = self.blockchain().get_caller();
+        let user_id = NonZeroUsize::new(self.get_user_id(&caller))
+            .unwrap_or_else(|| sc_panic!("only delegators can reclaim stake"));
+
+        let mut waiting = self.get_user_stake_of_type(user_id.get(), FundType::Waiting);
+        let mut active = self.get_user_stake_of_type(user_id.get(), FundType::Active);
+        let amount = &waiting + &active;
+        require!(amount > 0, "no stake to reclaim");
+        require!(
+            active <= self.total_unprotected(),
+            "stake is locked in staked nodes"
+        );
+
+        // stake that voted on open proposals must stay
+        self.require_unstake_keeps_locked_voting_power(&caller, &amount);
+
+        self.swap_user_waiting_to_withdraw_only(user_id.get(), &mut waiting);
+        require!(waiting == 0, "error converting Waiting to WithdrawOnly");
+
+        // compute rewards before converting Active -> WithdrawOnly
+        self.compute_one_user_reward(OWNER_USER_ID);
+        self.compute_one_user_reward(user_id);
+
+        self.swap_user_active_to_withdraw_only(user_id.get(), &mut active);
+        require!(active == 0, "error converting Active to WithdrawOnly");
+
+        self.bootstrap_stake_reclaimed_event(&caller, &amount);
+        amount
+    }
+
     #[view(getUnStakeable)]
     fn get_unstakeable(&self, user_address: ManagedAddress) -> BigUint {
         let user_id = self.get_user_id(&user_address);
@@ -94,13 +149,13 @@ pub trait UserStakeEndpointsModule:
 
     #[endpoint(unBond)]
     fn unbond_user(&self) -> BigUint {
-        require!(self.not_paused(), "contract paused");
+        self.require_not_paused_or_emergency();
 
         let caller = self.blockchain().get_caller();
         let caller_id = self.get_user_id(&caller);
         require!(caller_id > 0, "unknown caller");
 
-        let n_blocks_before_unbond = self.get_n_blocks_before_unbond();
+        let n_blocks_before_unbond = self.get_n_blocks_before_unbond_now();
         let _ = self.swap_eligible_deferred_to_withdraw(caller_id, n_blocks_before_unbond, || {
             self.blockchain().get_gas_left() < UNBOND_GASLIMIT
         });
@@ -117,15 +172,83 @@ pub trait UserStakeEndpointsModule:
         amount_liquidated
     }
 
+    /// Once the contract is sunset the nodes are already unbonded,
+    /// so all deferred payments can be unbonded right away.
+    fn get_n_blocks_before_unbond_now(&self) -> u64 {
+        if self.sunset_phase().get() == SunsetPhase::Withdrawing {
+            0
+        } else {
+            self.get_n_blocks_before_unbond()
+        }
+    }
+
     #[view(getUnBondable)]
     fn get_unbondable(&self, user_address: ManagedAddress) -> BigUint {
         let user_id = self.get_user_id(&user_address);
         if user_id == 0 {
             BigUint::zero()
         } else {
-            let n_blocks_before_unbond = self.get_n_blocks_before_unbond();
+            let n_blocks_before_unbond = self.get_n_blocks_before_unbond_now();
             self.eligible_deferred_payment(user_id, n_blocks_before_unbond)
                 + self.get_user_stake_of_type(user_id, FundType::WithdrawOnly)
         }
     }
+
+    /// Lists all deferred payment tranches of a user, in the order in which they are paid out.
+    /// For each tranche it yields: amount, number of blocks remaining until it can be unbonded,
+    /// and the block nonce from which `unBond` will pay it out.
+    /// Estimates are based on the current `n_blocks_before_unbond` setting.
+    #[view(getUserUnBondEta)]
+    fn get_user_unbond_eta(
+        &self,
+        user_address: ManagedAddress,
+    ) -> MultiValueEncoded<MultiValue3<BigUint, u64, u64>> {
+        let mut result = MultiValueEncoded::new();
+        let user_id = self.get_user_id(&user_address);
+        if user_id == 0 {
+            return result;
+        }
+
+        let current_bl_nonce = self.blockchain().get_block_nonce();
+        let n_blocks_before_unbond = self.get_n_blocks_before_unbond_now();
+        self.foreach_fund_by_user_type(
+            user_id,
+            FundType::DeferredPayment,
+            SwapDirection::Forwards,
+            |fund_item| {
+                if let FundDescription::DeferredPayment { created } = fund_item.fund_desc {
+                    let claim_nonce = created + n_blocks_before_unbond;
+                    let blocks_remaining = claim_nonce.saturating_sub(current_bl_nonce);
+                    result.push((fund_item.balance, blocks_remaining, claim_nonce).into());
+                }
+            },
+        );
+        result
+    }
+
+    /// Yields the amount the user will be able to unbond next, and the block nonce from which it is possible.
+    /// If something can already be unbonded, it yields the currently unbondable amount and the current block nonce.
+    /// Otherwise, it yields the sum of the tranches that become eligible first, and their claim nonce.
+    /// Yields (0, 0) if there is nothing pending.
+    #[view(getUserNextUnBond)]
+    fn get_user_next_unbond(&self, user_address: ManagedAddress) -> MultiValue2<BigUint, u64> {
+        let unbondable = self.get_unbondable(user_address.clone());
+        if unbondable > 0 {
+            return (unbondable, self.blockchain().get_block_nonce()).into();
+        }
+
+        let mut next_amount = BigUint::zero();
+        let mut next_claim_nonce = 0u64;
+        for eta in self.get_user_unbond_eta(user_address).into_iter() {
+            let (amount, _, claim_nonce) = eta.into_tuple();
+            if next_amount == 0 || claim_nonce < next_claim_nonce {
+                next_amount = amount;
+                next_claim_nonce = claim_nonce;
+            } else if claim_nonce == next_claim_nonce {
+                next_amount += amount;
+            }
+        }
+
+        (next_amount, next_claim_nonce).into()
+    }
 }
@@ -67,6 +67,7 @@ pub trait UserStakeStateModule:
 
             // swap waiting -> active, but no more than fillable
             // no need to worry about rewards here, because there aren't any
+            // the waiting activation mode is also irrelevant, since waiting stake gets activated as soon as it arrives
             let _ = self.swap_waiting_to_active(&mut fillable_active_stake, || false);
             if fillable_active_stake == 0 {
                 // this happens only when waiting was enough to fill the delegation cap
@@ -160,20 +161,61 @@ pub trait UserStakeStateModule:
     /// Invariant: should never return error.
     #[view(validateDelegationCapInvariant)]
     fn validate_delegation_cap_invariant(&self) {
+        require!(
+            self.is_delegation_cap_invariant_valid(),
+            "delegation cap invariant violated"
+        );
+    }
+
+    fn is_delegation_cap_invariant_valid(&self) -> bool {
         let total_active = self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::Active);
         let total_unstaked = self.get_user_stake_of_type(USER_STAKE_TOTALS_ID, FundType::UnStaked);
         let total_delegation_cap = self.get_total_delegation_cap();
 
         if self.is_bootstrap_mode() {
-            require!(
-                &total_active + &total_unstaked < total_delegation_cap,
-                "delegation cap invariant violated"
-            );
+            &total_active + &total_unstaked < total_delegation_cap
         } else {
-            require!(
-                &total_active + &total_unstaked == total_delegation_cap,
-                "delegation cap invariant violated"
-            );
+            &total_active + &total_unstaked == total_delegation_cap
+        }
+    }
+
+    /// Epoch in which an UnStaked fund item was created.
+    /// Not known for fund items created before it was recorded.
+    #[storage_mapper("unstaked_fund_epoch")]
+    fn unstaked_fund_epoch(&self, fund_id: usize) -> SingleValueMapper<u64>;
+
+    /// The fund items with a recorded epoch, oldest first.
+    #[storage_mapper("unstaked_fund_epoch_ids")]
+    fn unstaked_fund_epoch_ids(&self) -> QueueMapper<usize>;
+
+    /// Records the epoch of the UnStaked fund item just created for the user.
+    /// Coalesced fund items keep the epoch they were created in.
+    fn record_unstaked_fund_epoch(&self, user_id: usize) {
+        self.clear_old_unstaked_fund_epochs();
+
+        let fund_id = self
+            .fund_list_by_user(user_id, FundType::UnStaked)
+            .get()
+            .last;
+        if fund_id > 0 && self.unstaked_fund_epoch(fund_id).is_empty() {
+            self.unstaked_fund_epoch(fund_id)
+                .set(self.blockchain().get_block_epoch());
+            self.unstaked_fund_epoch_ids().push_back(fund_id);
+        }
+    }
+
+    /// UnStaked fund items are converted and deleted oldest first,
+    /// so the epochs of the deleted items are cleared from the front of the queue.
+    fn clear_old_unstaked_fund_epochs(&self) {
+        let mut fund_ids = self.unstaked_fund_epoch_ids();
+        while let Some(fund_id) = fund_ids.front() {
+            let fund_item = self.fund_by_id(fund_id);
+            if !fund_item.is_empty() && fund_item.get().fund_desc.fund_type() == FundType::UnStaked {
+                break;
+            }
+
+            fund_ids.pop_front();
+            self.unstaked_fund_epoch(fund_id).clear();
         }
     }
 }
@@ -49,6 +49,11 @@ fn decrease_cap_in_bootstrap_mode_go() {
     world().run("scenarios/decrease_cap_in_bootstrap_mode.scen.json");
 }
 
+#[test]
+fn decrease_cap_pro_rata_go() {
+    world().run("scenarios/decrease_cap_pro_rata.scen.json");
+}
+
 #[test]
 fn genesis_addr_fix_go() {
     world().run("scenarios/genesis_addr_fix.scen.json");
@@ -89,6 +94,11 @@ fn unbond_go() {
     world().run("scenarios/unbond.scen.json");
 }
 
+#[test]
+fn unbond_eta_go() {
+    world().run("scenarios/unbond_eta.scen.json");
+}
+
 #[test]
 fn unbond_from_waiting_go() {
     world().run("scenarios/unbond_from_waiting.scen.json");
@@ -143,3 +153,133 @@ fn version_full_go() {
 fn version_update_go() {
     world().run("scenarios/version_update.scen.json");
 }
+
+#[test]
+fn waiting_activation_mode_go() {
+    world().run("scenarios/waiting_activation_mode.scen.json");
+}
+
+#[test]
+fn simulation_go() {
+    world().run("scenarios/simulation.scen.json");
+}
+
+#[test]
+fn governance_pool_vote_go() {
+    world().run("scenarios/governance_pool_vote.scen.json");
+}
+
+#[test]
+fn governance_vote_go() {
+    world().run("scenarios/governance_vote.scen.json");
+}
+
+#[test]
+fn governance_vote_lock_go() {
+    world().run("scenarios/governance_vote_lock.scen.json");
+}
+
+#[test]
+fn governance_representative_go() {
+    world().run("scenarios/governance_representative.scen.json");
+}
+
+#[test]
+fn auto_stake_nodes_go() {
+    world().run("scenarios/auto_stake_nodes.scen.json");
+}
+
+#[test]
+fn nodes_by_state_go() {
+    world().run("scenarios/nodes_by_state.scen.json");
+}
+
+#[test]
+fn node_metadata_go() {
+    world().run("scenarios/node_metadata.scen.json");
+}
+
+#[test]
+fn node_signatures_go() {
+    world().run("scenarios/node_signatures.scen.json");
+}
+
+#[test]
+fn node_key_rotation_go() {
+    world().run("scenarios/node_key_rotation.scen.json");
+}
+
+#[test]
+fn recover_stuck_nodes_go() {
+    world().run("scenarios/recover_stuck_nodes.scen.json");
+}
+
+#[test]
+fn reconcile_node_states_go() {
+    world().run("scenarios/reconcile_node_states.scen.json");
+}
+
+#[test]
+fn stake_top_up_go() {
+    world().run("scenarios/stake_top_up.scen.json");
+}
+
+#[test]
+fn node_jail_history_go() {
+    world().run("scenarios/node_jail_history.scen.json");
+}
+
+#[test]
+fn auction_mock_lifecycle_go() {
+    world().run("scenarios/auction_mock_lifecycle.scen.json");
+}
+
+#[test]
+fn auction_call_history_go() {
+    world().run("scenarios/auction_call_history.scen.json");
+}
+
+#[test]
+fn accounting_audit_go() {
+    world().run("scenarios/accounting_audit.scen.json");
+}
+
+#[test]
+fn emergency_mode_go() {
+    world().run("scenarios/emergency_mode.scen.json");
+}
+
+#[test]
+fn sunset_go() {
+    world().run("scenarios/sunset.scen.json");
+}
+
+#[test]
+fn escape_hatch_go() {
+    world().run("scenarios/escape_hatch.scen.json");
+}
+
+#[test]
+fn bootstrap_deadline_go() {
+    world().run("scenarios/bootstrap_deadline.scen.json");
+}
+
+#[test]
+fn unstake_top_up_go() {
+    world().run("scenarios/unstake_top_up.scen.json");
+}
+
+#[test]
+fn sunset_top_up_go() {
+    world().run("scenarios/sunset_top_up.scen.json");
+}
+
+#[test]
+fn waiting_activation_many_users_go() {
+    world().run("scenarios/waiting_activation_many_users.scen.json");
+}
+
+#[test]
+fn simulation_waiting_activation_go() {
+    world().run("scenarios/simulation_waiting_activation.scen.json");
+}
@@ -7,6 +7,10 @@ fn world() -> ScenarioWorld {
         "file:../auction-mock/output/auction-mock.wasm",
         auction_mock::ContractBuilder,
     );
+    blockchain.register_contract(
+        "file:../governance-mock/output/governance-mock.wasm",
+        governance_mock::ContractBuilder,
+    );
 
     blockchain.register_partial_contract::<delegation_latest::AbiProvider, _>(
         "file:output/delegation_latest_full.wasm",
@@ -67,6 +71,11 @@ fn decrease_cap_in_bootstrap_mode_rs() {
     world().run("scenarios/decrease_cap_in_bootstrap_mode.scen.json");
 }
 
+#[test]
+fn decrease_cap_pro_rata_rs() {
+    world().run("scenarios/decrease_cap_pro_rata.scen.json");
+}
+
 #[test]
 fn genesis_addr_fix_rs() {
     world().run("scenarios/genesis_addr_fix.scen.json");
@@ -107,6 +116,11 @@ fn unbond_rs() {
     world().run("scenarios/unbond.scen.json");
 }
 
+#[test]
+fn unbond_eta_rs() {
+    world().run("scenarios/unbond_eta.scen.json");
+}
+
 #[test]
 fn unbond_from_waiting_rs() {
     world().run("scenarios/unbond_from_waiting.scen.json");
@@ -162,3 +176,133 @@ fn version_full_rs() {
 fn version_update_rs() {
     world().run("scenarios/version_update.scen.json");
 }
+
+#[test]
+fn waiting_activation_mode_rs() {
+    world().run("scenarios/waiting_activation_mode.scen.json");
+}
+
+#[test]
+fn simulation_rs() {
+    world().run("scenarios/simulation.scen.json");
+}
+
+#[test]
+fn governance_pool_vote_rs() {
+    world().run("scenarios/governance_pool_vote.scen.json");
+}
+
+#[test]
+fn governance_vote_rs() {
+    world().run("scenarios/governance_vote.scen.json");
+}
+
+#[test]
+fn governance_vote_lock_rs() {
+    world().run("scenarios/governance_vote_lock.scen.json");
+}
+
+#[test]
+fn governance_representative_rs() {
+    world().run("scenarios/governance_representative.scen.json");
+}
+
+#[test]
+fn auto_stake_nodes_rs() {
+    world().run("scenarios/auto_stake_nodes.scen.json");
+}
+
+#[test]
+fn nodes_by_state_rs() {
+    world().run("scenarios/nodes_by_state.scen.json");
+}
+
+#[test]
+fn node_metadata_rs() {
+    world().run("scenarios/node_metadata.scen.json");
+}
+
+#[test]
+fn node_signatures_rs() {
+    world().run("scenarios/node_signatures.scen.json");
+}
+
+#[test]
+fn node_key_rotation_rs() {
+    world().run("scenarios/node_key_rotation.scen.json");
+}
+
+#[test]
+fn recover_stuck_nodes_rs() {
+    world().run("scenarios/recover_stuck_nodes.scen.json");
+}
+
+#[test]
+fn reconcile_node_states_rs() {
+    world().run("scenarios/reconcile_node_states.scen.json");
+}
+
+#[test]
+fn stake_top_up_rs() {
+    world().run("scenarios/stake_top_up.scen.json");
+}
+
+#[test]
+fn node_jail_history_rs() {
+    world().run("scenarios/node_jail_history.scen.json");
+}
+
+#[test]
+fn auction_mock_lifecycle_rs() {
+    world().run("scenarios/auction_mock_lifecycle.scen.json");
+}
+
+#[test]
+fn auction_call_history_rs() {
+    world().run("scenarios/auction_call_history.scen.json");
+}
+
+#[test]
+fn accounting_audit_rs() {
+    world().run("scenarios/accounting_audit.scen.json");
+}
+
+#[test]
+fn emergency_mode_rs() {
+    world().run("scenarios/emergency_mode.scen.json");
+}
+
+#[test]
+fn sunset_rs() {
+    world().run("scenarios/sunset.scen.json");
+}
+
+#[test]
+fn escape_hatch_rs() {
+    world().run("scenarios/escape_hatch.scen.json");
+}
+
+#[test]
+fn bootstrap_deadline_rs() {
+    world().run("scenarios/bootstrap_deadline.scen.json");
+}
+
+#[test]
+fn unstake_top_up_rs() {
+    world().run("scenarios/unstake_top_up.scen.json");
+}
+
+#[test]
+fn sunset_top_up_rs() {
+    world().run("scenarios/sunset_top_up.scen.json");
+}
+
+#[test]
+fn waiting_activation_many_users_rs() {
+    world().run("scenarios/waiting_activation_many_users.scen.json");
+}
+
+#[test]
+fn simulation_waiting_activation_rs() {
+    world().run("scenarios/simulation_waiting_activation.scen.json");
+}
@@ -5,9 +5,9 @@
 ////////////////////////////////////////////////////
 
 // Upgrade:                              1
-// Endpoints:                           81
+// Endpoints:                          148
 // Async Callback:                       1
-// Total number of exported functions:  83
+// Total number of exported functions: 150
 
 #![no_std]
 
@@ -22,11 +22,19 @@ multiversx_sc_wasm_adapter::endpoints! {
         getNumNodes => num_nodes
         getNodeId => get_node_id
         getNodeSignature => get_node_signature_endpoint
+        getNodePendingSince => get_node_pending_since_endpoint
         getNodeState => get_node_state_endpoint
+        getNumNodesByState => get_num_nodes_by_state
+        getNodesByState => get_nodes_by_state
         getAllNodeStates => get_all_node_states
+        setNodeMetadata => set_node_metadata
+        getNodeMetadata => get_node_metadata_endpoint
+        getAllNodeStatesWithMetadata => get_all_node_states_with_metadata
         getNodeBlockNonceOfUnstake => get_node_bl_nonce_of_unstake_endpoint
         addNodes => add_nodes
         removeNodes => remove_nodes
+        updateNodeSignature => update_node_signature
+        replaceNodeKey => replace_node_key
         getUserId => get_user_id
         getUserAddress => get_user_address
         getNumUsers => get_num_users
@@ -52,24 +60,47 @@ multiversx_sc_wasm_adapter::endpoints! {
         getFullWaitingList => get_full_waiting_list
         getFullActiveList => get_full_active_list
         stakeNodes => stake_nodes
+        autoStakeNodes => auto_stake_nodes
+        setAutoStakePerNode => set_auto_stake_per_node
+        setAutoStakeQueue => set_auto_stake_queue
+        getAutoStakeQueue => get_auto_stake_queue
+        getAutoStakePerNode => auto_stake_per_node
+        stakeTopUp => stake_top_up
+        getTotalTopUp => total_top_up
+        unStakeTopUp => unstake_top_up
+        unBondTopUp => unbond_top_up
+        getUnStakedTopUp => unstaked_top_up
         unStakeNodes => unstake_nodes_endpoint
         unStakeNodesAndTokens => unstake_nodes_and_tokens_endpoint
         forceNodeUnBondPeriod => force_node_unbond_period
         unBondNodes => unbond_specific_nodes_endpoint
         unBondAllPossibleNodes => unbond_all_possible_nodes
+        setPendingNodeTimeout => set_pending_node_timeout
+        getPendingNodeTimeout => pending_node_timeout
+        recoverStuckNodes => recover_stuck_nodes
+        reconcileNodeStates => reconcile_node_states
+        syncAuctionStake => sync_auction_stake
+        getAuctionStakeReport => auction_stake_report
         claimUnusedFunds => claim_unused_funds
         unJailNodes => unjail_nodes
+        unJailNodesFromRewards => unjail_nodes_from_rewards
         unStakeTokens => unstake_tokens
         unBondTokens => unbond_tokens
+        getNodeJailHistory => get_node_jail_history_endpoint
         getAuctionContractAddress => get_auction_contract_address
         getServiceFee => get_service_fee
         getTotalDelegationCap => get_total_delegation_cap
         isBootstrapMode => is_bootstrap_mode
+        getBootstrapDeadline => bootstrap_deadline
+        setBootstrapDeadline => set_bootstrap_deadline_endpoint
+        isBootstrapDeadlinePassed => is_bootstrap_deadline_passed
         getOwnerMinStakeShare => get_owner_min_stake_share
         getNumBlocksBeforeUnBond => get_n_blocks_before_unbond
         setNumBlocksBeforeUnBond => set_n_blocks_before_unbond_endpoint
         getMinimumStake => get_minimum_stake
         setMinimumStake => set_minimum_stake_endpoint
+        getForcedUnStakeMode => forced_unstake_mode
+        getWaitingActivationMode => waiting_activation_mode
         getGlobalOperationCheckpoint => global_op_checkpoint
         isGlobalOperationInProgress => is_global_op_in_progress
         getTotalCumulatedRewards => get_total_cumulated_rewards
@@ -80,25 +111,61 @@ multiversx_sc_wasm_adapter::endpoints! {
         validateDelegationCapInvariant => validate_delegation_cap_invariant
         continueGlobalOperation => continue_global_operation_endpoint
         modifyTotalDelegationCap => modify_total_delegation_cap
+        lowerDelegationCapToRaised => lower_delegation_cap_to_raised
+        setForcedUnStakeMode => set_forced_unstake_mode_endpoint
+        setWaitingActivationMode => set_waiting_activation_mode_endpoint
         setServiceFee => set_service_fee_endpoint
         claimRewards => claim_rewards
         stake => stake_endpoint
         unStake => unstake_endpoint
+        reclaimBootstrapStake => reclaim_bootstrap_stake
         getUnStakeable => get_unstakeable
         unBond => unbond_user
         getUnBondable => get_unbondable
+        getUserUnBondEta => get_user_unbond_eta
+        getUserNextUnBond => get_user_next_unbond
         dustCleanupCheckpoint => dust_cleanup_checkpoint
         countDustItemsWaitingList => count_dust_items_waiting_list
         countDustItemsActive => count_dust_items_active
         dustCleanupWaitingList => dust_cleanup_waiting_list
         dustCleanupActive => dust_cleanup_active
+        getAccountingAuditResult => get_accounting_audit_result
+        auditAccounting => audit_accounting
+        isEmergencyMode => emergency_mode
+        getEmergencyModeReason => emergency_mode_reason
+        triggerEmergencyMode => trigger_emergency_mode
+        exitEmergencyMode => exit_emergency_mode
+        simulateModifyTotalDelegationCap => simulate_modify_total_delegation_cap
+        simulateUnstake => simulate_unstake
+        simulateServiceFee => simulate_service_fee
+        isSunset => is_sunset
+        startSunset => start_sunset
+        continueSunset => continue_sunset
+        getSunsetStatus => get_sunset_status
+        setEscapeHatchEpochs => set_escape_hatch_epochs
+        getEscapeHatchEpochs => escape_hatch_epochs
+        getEscapeHatchEpoch => get_escape_hatch_epoch
+        escapeHatch => escape_hatch
         dnsRegister => dns_register
         setFeatureFlag => set_feature_flag_endpoint
         pause => pause_endpoint
         unpause => unpause_endpoint
         isPaused => paused_status
         delegateVote => delegate_vote
+        assignRepresentative => assign_representative
+        revokeRepresentative => revoke_representative
+        getRepresentativeVotingPower => get_representative_voting_power
+        setPoolVote => set_pool_vote
+        castPoolVote => cast_pool_vote
         getVotingPower => get_voting_power
+        registerProposal => register_proposal
+        getLockedVotingPower => get_locked_voting_power
+        getUserVote => get_user_vote
+        getProposalTally => get_proposal_tally
+        getProposalEndNonce => proposal_end_nonce
+        getRepresentative => representative
+        getRepresentedUsers => represented_users
+        getPoolVote => pool_vote
     )
 }
 
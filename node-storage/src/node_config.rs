@@ -1,15 +1,19 @@
-use crate::types::{BLSKey, BLSSignature, BLSStatusMultiArg, NodeState};
+use crate::types::{BLSKey, BLSSignature, BLSStatusMultiArg, NodeMetadata, NodeState};
 
 multiversx_sc::imports!();
 
 pub const MAX_NODES_PER_OPERATION: usize = 100;
 
+/// Maximum length of each of the text fields in the node metadata.
+pub const MAX_NODE_METADATA_FIELD_LENGTH: usize = 128;
+
 pub type NodeIndexArrayVec = ArrayVec<usize, MAX_NODES_PER_OPERATION>;
 
 /// This module manages the validator node info:
 /// - how many nodes there are,
 /// - what BLS keys they have.
 /// - what BLS signatures they have
+/// - who operates them and where they are hosted.
 ///
 #[multiversx_sc::derive::module]
 pub trait NodeConfigModule {
@@ -41,6 +45,23 @@ pub trait NodeConfigModule {
     #[storage_set("node_signature")]
     fn set_node_signature(&self, node_id: usize, node_signature: BLSSignature<Self::Api>);
 
+    /// The signature must be the BLS signature of the contract address, made with the node key.
+    /// This is the same check the auction SC does when staking, so bad signatures are caught early.
+    /// Fails the transaction directly if the signature is invalid.
+    fn require_valid_node_signature(
+        &self,
+        bls_key: &BLSKey<Self::Api>,
+        bls_sig: &BLSSignature<Self::Api>,
+    ) {
+        let sc_address = self.blockchain().get_sc_address();
+
+        self.crypto().verify_bls(
+            bls_key.bytes.as_managed_buffer(),
+            sc_address.as_managed_buffer(),
+            bls_sig.bytes.as_managed_buffer(),
+        );
+    }
+
     #[view(getNodeSignature)]
     fn get_node_signature_endpoint(
         &self,
@@ -59,7 +80,62 @@ pub trait NodeConfigModule {
     fn get_node_state(&self, node_id: usize) -> NodeState;
 
     #[storage_set("node_state")]
-    fn set_node_state(&self, node_id: usize, node_state: NodeState);
+    fn store_node_state(&self, node_id: usize, node_state: NodeState);
+
+    /// Changes the state of a node, keeping the per-state indexes in sync.
+    fn set_node_state(&self, node_id: usize, node_state: NodeState) {
+        let old_state = self.get_node_state(node_id);
+        self.nodes_by_state(old_state.discriminant())
+            .swap_remove(&node_id);
+        self.nodes_by_state(node_state.discriminant())
+            .insert(node_id);
+        if node_state.is_pending() {
+            self.node_pending_since(node_id)
+                .set(self.blockchain().get_block_nonce());
+        } else {
+            self.node_pending_since(node_id).clear();
+        }
+        self.store_node_state(node_id, node_state);
+    }
+
+    /// Block nonce when the node entered its current pending state.
+    /// Zero for nodes that became pending before this was recorded.
+    #[storage_mapper("node_pending_since")]
+    fn node_pending_since(&self, node_id: usize) -> SingleValueMapper<u64>;
+
+    #[view(getNodePendingSince)]
+    fn get_node_pending_since_endpoint(&self, bls_key: BLSKey<Self::Api>) -> OptionalValue<u64> {
+        let node_id = self.get_node_id(&bls_key);
+        if node_id == 0 || !self.get_node_state(node_id).is_pending() {
+            OptionalValue::None
+        } else {
+            OptionalValue::Some(self.node_pending_since(node_id).get())
+        }
+    }
+
+    /// Index of node ids, grouped by state discriminant.
+    /// Avoids scanning all node ids when only nodes in a certain state are relevant.
+    #[storage_mapper("nodes_by_state")]
+    fn nodes_by_state(&self, state_discriminant: u8) -> UnorderedSetMapper<usize>;
+
+    /// Set once the per-state indexes cover all nodes.
+    /// Contracts deployed before the indexes existed build them on upgrade.
+    #[storage_mapper("node_state_indexes_built")]
+    fn node_state_indexes_built(&self) -> SingleValueMapper<bool>;
+
+    fn build_node_state_indexes_if_necessary(&self) {
+        if self.node_state_indexes_built().get() {
+            return;
+        }
+
+        let num_nodes = self.num_nodes().get();
+        for node_id in 1..=num_nodes {
+            let node_state = self.get_node_state(node_id);
+            self.nodes_by_state(node_state.discriminant())
+                .insert(node_id);
+        }
+        self.node_state_indexes_built().set(true);
+    }
 
     #[view(getNodeState)]
     fn get_node_state_endpoint(&self, bls_key: BLSKey<Self::Api>) -> NodeState {
@@ -71,6 +147,33 @@ pub trait NodeConfigModule {
         }
     }
 
+    /// Number of nodes currently in the given state (by discriminant).
+    #[view(getNumNodesByState)]
+    fn get_num_nodes_by_state(&self, state_discriminant: u8) -> usize {
+        self.nodes_by_state(state_discriminant).len()
+    }
+
+    /// Paginated list of the BLS keys of nodes in the given state (by discriminant).
+    /// `from` is a 0-based position in the index, the order is not related to node ids.
+    #[view(getNodesByState)]
+    fn get_nodes_by_state(
+        &self,
+        state_discriminant: u8,
+        from: usize,
+        count: usize,
+    ) -> MultiValueEncoded<BLSKey<Self::Api>> {
+        let nodes = self.nodes_by_state(state_discriminant);
+        let end = core::cmp::min(from.saturating_add(count), nodes.len());
+        let mut result = MultiValueEncoded::new();
+        for index in from..end {
+            // UnorderedSetMapper positions start from 1
+            result.push(self.get_node_id_to_bls(nodes.get_by_index(index + 1)));
+        }
+        result
+    }
+
+    /// Lists every node ever added, including removed ones, in node id order.
+    /// Prefer `getNodesByState` when only some states are of interest.
     #[view(getAllNodeStates)]
     fn get_all_node_states(&self) -> MultiValueEncoded<MultiValue2<BLSKey<Self::Api>, u8>> {
         let num_nodes = self.num_nodes().get();
@@ -84,6 +187,82 @@ pub trait NodeConfigModule {
         result
     }
 
+    #[storage_mapper("node_metadata")]
+    fn node_metadata(&self, node_id: usize) -> SingleValueMapper<NodeMetadata<Self::Api>>;
+
+    fn get_node_metadata_or_default(&self, node_id: usize) -> NodeMetadata<Self::Api> {
+        let mapper = self.node_metadata(node_id);
+        if mapper.is_empty() {
+            NodeMetadata::default()
+        } else {
+            mapper.get()
+        }
+    }
+
+    fn record_node_added(&self, node_id: usize) {
+        let mut metadata = self.get_node_metadata_or_default(node_id);
+        metadata.added_at = self.blockchain().get_block_nonce();
+        self.node_metadata(node_id).set(metadata);
+    }
+
+    /// Configures the operator metadata of a node. The added-at nonce is kept.
+    #[only_owner]
+    #[endpoint(setNodeMetadata)]
+    fn set_node_metadata(
+        &self,
+        bls_key: BLSKey<Self::Api>,
+        label: ManagedBuffer,
+        hosting_provider: ManagedBuffer,
+        region: ManagedBuffer,
+        operator: ManagedAddress,
+    ) {
+        let node_id = self.get_node_id(&bls_key);
+        require!(node_id != 0, "node not registered");
+        require!(
+            label.len() <= MAX_NODE_METADATA_FIELD_LENGTH
+                && hosting_provider.len() <= MAX_NODE_METADATA_FIELD_LENGTH
+                && region.len() <= MAX_NODE_METADATA_FIELD_LENGTH,
+            "node metadata field too long"
+        );
+
+        let mut metadata = self.get_node_metadata_or_default(node_id);
+        metadata.label = label;
+        metadata.hosting_provider = hosting_provider;
+        metadata.region = region;
+        metadata.operator = operator;
+        self.node_metadata(node_id).set(metadata);
+    }
+
+    #[view(getNodeMetadata)]
+    fn get_node_metadata_endpoint(
+        &self,
+        bls_key: BLSKey<Self::Api>,
+    ) -> OptionalValue<NodeMetadata<Self::Api>> {
+        let node_id = self.get_node_id(&bls_key);
+        if node_id == 0 {
+            OptionalValue::None
+        } else {
+            OptionalValue::Some(self.get_node_metadata_or_default(node_id))
+        }
+    }
+
+    /// Same as `getAllNodeStates`, with the metadata of each node.
+    #[view(getAllNodeStatesWithMetadata)]
+    fn get_all_node_states_with_metadata(
+        &self,
+    ) -> MultiValueEncoded<MultiValue3<BLSKey<Self::Api>, u8, NodeMetadata<Self::Api>>> {
+        let num_nodes = self.num_nodes().get();
+        let mut result = MultiValueEncoded::new();
+        for i in 1..num_nodes + 1 {
+            result.push(MultiValue3::from((
+                self.get_node_id_to_bls(i),
+                self.get_node_state(i).discriminant(),
+                self.get_node_metadata_or_default(i),
+            )));
+        }
+        result
+    }
+
     #[view(getNodeBlockNonceOfUnstake)]
     fn get_node_bl_nonce_of_unstake_endpoint(
         &self,
@@ -108,8 +287,12 @@ pub trait NodeConfigModule {
         >,
     ) {
         let mut num_nodes = self.num_nodes().get();
+        // keys added earlier in the same call are already registered, these tell them apart
+        let first_new_node_id = num_nodes + 1;
+        let mut readded_node_ids = ManagedVec::<Self::Api, usize>::new();
         for bls_sig_pair_arg in bls_keys_signatures.into_iter() {
             let (bls_key, bls_sig) = bls_sig_pair_arg.into_tuple();
+            self.require_valid_node_signature(&bls_key, &bls_sig);
             let mut node_id = self.get_node_id(&bls_key);
             if node_id == 0 {
                 num_nodes += 1;
@@ -118,9 +301,14 @@ pub trait NodeConfigModule {
                 self.set_node_id_to_bls(node_id, &bls_key);
                 self.set_node_state(node_id, NodeState::Inactive);
                 self.set_node_signature(node_id, bls_sig);
+                self.record_node_added(node_id);
             } else if self.get_node_state(node_id) == NodeState::Removed {
                 self.set_node_state(node_id, NodeState::Inactive);
                 self.set_node_signature(node_id, bls_sig);
+                self.record_node_added(node_id);
+                readded_node_ids.push(node_id);
+            } else if node_id >= first_new_node_id || readded_node_ids.contains(&node_id) {
+                sc_panic!("duplicate BLS key provided");
             } else {
                 sc_panic!("node already registered");
             }
@@ -142,6 +330,62 @@ pub trait NodeConfigModule {
         }
     }
 
+    /// Replaces the stored signature of an inactive node, e.g. after a bad signature was registered.
+    #[only_owner]
+    #[endpoint(updateNodeSignature)]
+    fn update_node_signature(&self, bls_key: BLSKey<Self::Api>, bls_sig: BLSSignature<Self::Api>) {
+        let node_id = self.get_node_id(&bls_key);
+        self.require_inactive_registered_node(node_id);
+        self.require_valid_node_signature(&bls_key, &bls_sig);
+
+        self.set_node_signature(node_id, bls_sig);
+        self.update_node_signature_event(&bls_key);
+    }
+
+    /// Swaps the BLS key of an inactive node for a key that was never registered.
+    /// The node keeps its id, so its state, metadata and queue positions are kept too.
+    /// The old key is unregistered and can later be added again as a new node.
+    #[only_owner]
+    #[endpoint(replaceNodeKey)]
+    fn replace_node_key(
+        &self,
+        old_bls_key: BLSKey<Self::Api>,
+        new_bls_key: BLSKey<Self::Api>,
+        new_bls_sig: BLSSignature<Self::Api>,
+    ) {
+        let node_id = self.get_node_id(&old_bls_key);
+        self.require_inactive_registered_node(node_id);
+        require!(
+            self.get_node_id(&new_bls_key) == 0,
+            "new key already registered"
+        );
+        self.require_valid_node_signature(&new_bls_key, &new_bls_sig);
+
+        self.set_node_bls_to_id(&old_bls_key, 0);
+        self.set_node_bls_to_id(&new_bls_key, node_id);
+        self.set_node_id_to_bls(node_id, &new_bls_key);
+        self.set_node_signature(node_id, new_bls_sig);
+        self.replace_node_key_event(&old_bls_key, &new_bls_key);
+    }
+
+    fn require_inactive_registered_node(&self, node_id: usize) {
+        require!(node_id != 0, "node not registered");
+        require!(
+            self.get_node_state(node_id) == NodeState::Inactive,
+            "only inactive nodes can be changed"
+        );
+    }
+
+    #[event("updateNodeSignature")]
+    fn update_node_signature_event(&self, #[indexed] bls_key: &BLSKey<Self::Api>);
+
+    #[event("replaceNodeKey")]
+    fn replace_node_key_event(
+        &self,
+        #[indexed] old_bls_key: &BLSKey<Self::Api>,
+        #[indexed] new_bls_key: &BLSKey<Self::Api>,
+    );
+
     fn split_node_ids_by_err(
         &self,
         mut node_ids: NodeIndexArrayVec,
@@ -6,7 +6,7 @@ multiversx_sc::derive_imports!();
 pub const BLS_SIGNATURE_BYTE_LENGTH: usize = 48;
 
 #[type_abi]
-#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, ManagedVecItem)]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, ManagedVecItem, Clone)]
 pub struct BLSSignature<M: ManagedTypeApi> {
     pub bytes: ManagedByteArray<M, BLS_SIGNATURE_BYTE_LENGTH>,
 }
@@ -0,0 +1,34 @@
+multiversx_sc::imports!();
+multiversx_sc::derive_imports!();
+
+/// Operator information attached to a node, as configured by the owner.
+#[type_abi]
+#[derive(TopEncode, TopDecode, NestedEncode, NestedDecode, PartialEq, Clone, Debug)]
+pub struct NodeMetadata<M: ManagedTypeApi> {
+    /// Free-form name given to the node.
+    pub label: ManagedBuffer<M>,
+
+    /// Provider or data centre where the node is hosted.
+    pub hosting_provider: ManagedBuffer<M>,
+
+    pub region: ManagedBuffer<M>,
+
+    /// Address of the operator responsible for the node. Zero if not configured.
+    pub operator: ManagedAddress<M>,
+
+    /// Block nonce when the node was last added to the contract.
+    /// Zero for nodes added before metadata was recorded.
+    pub added_at: u64,
+}
+
+impl<M: ManagedTypeApi> Default for NodeMetadata<M> {
+    fn default() -> Self {
+        NodeMetadata {
+            label: ManagedBuffer::new(),
+            hosting_provider: ManagedBuffer::new(),
+            region: ManagedBuffer::new(),
+            operator: ManagedAddress::zero(),
+            added_at: 0,
+        }
+    }
+}
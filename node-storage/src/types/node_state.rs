@@ -45,4 +45,14 @@ impl NodeState {
             NodeState::Removed => 7,
         }
     }
+
+    /// True for the states waiting for an auction callback.
+    pub fn is_pending(&self) -> bool {
+        matches!(
+            self,
+            NodeState::PendingActivation
+                | NodeState::PendingDeactivation
+                | NodeState::PendingUnBond { .. }
+        )
+    }
 }
@@ -501,6 +501,40 @@ pub trait FundModule {
         }
     }
 
+    /// Traverses the type list starting from `current_id` and converts part of each element,
+    /// as indicated by the filter-transformation, until interrupted or the list ends.
+    /// Unlike `split_convert_max_by_type_with_checkpoint`, `current_id` = 0 means that the list has ended,
+    /// the caller is responsible for providing the first id.
+    fn split_convert_partial_by_type_with_checkpoint<F, I>(
+        &self,
+        current_id: &mut usize,
+        direction: SwapDirection,
+        mut filter_transform: F,
+        interrupt: I,
+    ) where
+        F: FnMut(&FundItem<Self::Api>) -> Option<(FundDescription, BigUint)>,
+        I: Fn() -> bool,
+    {
+        while *current_id > 0 && !interrupt() {
+            self.fund_by_id(*current_id).update(|fund_item| {
+                let next_id = match direction {
+                    // save next id now, because fund_item can be destroyed
+                    SwapDirection::Forwards => fund_item.type_list_next,
+                    SwapDirection::Backwards => fund_item.type_list_prev,
+                };
+
+                if let Some((transformed, mut amount)) = filter_transform(&*fund_item) {
+                    self.split_convert_individual_fund(
+                        &mut Some(&mut amount),
+                        transformed,
+                        &mut *fund_item,
+                    );
+                }
+                *current_id = next_id;
+            })
+        }
+    }
+
     fn first_id_of_user_type(
         &self,
         user_id: usize,
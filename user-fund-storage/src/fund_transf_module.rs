@@ -41,6 +41,17 @@ pub trait FundTransformationsModule: fund_module::FundModule {
         );
     }
 
+    fn swap_user_active_to_withdraw_only(&self, user_id: usize, remaining: &mut BigUint) {
+        let _ = self.split_convert_max_by_user(
+            Some(remaining),
+            user_id,
+            FundType::Active,
+            SwapDirection::Backwards,
+            |_| Some(FundDescription::WithdrawOnly),
+            || false,
+        );
+    }
+
     fn swap_waiting_to_active<I: Fn() -> bool>(
         &self,
         remaining: &mut BigUint,
@@ -112,6 +123,26 @@ pub trait FundTransformationsModule: fund_module::FundModule {
         (affected_users, stake_to_activate)
     }
 
+    fn swap_waiting_to_deferred_payment<I: Fn() -> bool>(
+        &self,
+        remaining: &mut BigUint,
+        interrupt: I,
+    ) {
+        let current_bl_nonce = self.blockchain().get_block_nonce();
+        let _ = self.split_convert_max_by_type(
+            Some(remaining),
+            FundType::Waiting,
+            SwapDirection::Forwards,
+            |_| {
+                Some(FundDescription::DeferredPayment {
+                    created: current_bl_nonce,
+                })
+            },
+            interrupt,
+            false,
+        );
+    }
+
     fn swap_unstaked_to_deferred_payment<I: Fn() -> bool>(
         &self,
         remaining: &mut BigUint,
@@ -152,6 +183,116 @@ pub trait FundTransformationsModule: fund_module::FundModule {
         );
     }
 
+    /// Converts from each fund item a share proportional to its balance,
+    /// i.e. `balance * total_swap / total_source`, rounded down.
+    /// Decreases `remaining` with every converted amount and never converts more than it.
+    /// Because of rounding, a small remainder can be left after the list ends.
+    fn swap_pro_rata<I: Fn() -> bool>(
+        &self,
+        current_id: &mut usize,
+        remaining: &mut BigUint,
+        total_swap: &BigUint,
+        total_source: &BigUint,
+        transformed: FundDescription,
+        interrupt: I,
+    ) {
+        self.split_convert_partial_by_type_with_checkpoint(
+            current_id,
+            SwapDirection::Forwards,
+            |fund_item| {
+                let mut share = &fund_item.balance * total_swap / total_source;
+                if share > *remaining {
+                    share = remaining.clone();
+                }
+                if share == 0 {
+                    return None;
+                }
+                *remaining -= &share;
+                Some((transformed, share))
+            },
+            interrupt,
+        );
+    }
+
+    fn swap_active_to_deferred_payment_pro_rata<I: Fn() -> bool>(
+        &self,
+        current_id: &mut usize,
+        remaining: &mut BigUint,
+        total_swap: &BigUint,
+        total_active: &BigUint,
+        interrupt: I,
+    ) {
+        let current_bl_nonce = self.blockchain().get_block_nonce();
+        self.swap_pro_rata(
+            current_id,
+            remaining,
+            total_swap,
+            total_active,
+            FundDescription::DeferredPayment {
+                created: current_bl_nonce,
+            },
+            interrupt,
+        );
+    }
+
+    fn swap_waiting_to_active_pro_rata<I: Fn() -> bool>(
+        &self,
+        current_id: &mut usize,
+        remaining: &mut BigUint,
+        total_swap: &BigUint,
+        total_waiting: &BigUint,
+        interrupt: I,
+    ) {
+        self.swap_pro_rata(
+            current_id,
+            remaining,
+            total_swap,
+            total_waiting,
+            FundDescription::Active,
+            interrupt,
+        );
+    }
+
+    /// Lists the users that have Waiting stake, in the order of their oldest Waiting fund item.
+    fn get_waiting_user_ids(&self) -> ManagedVec<usize> {
+        let mut user_ids = ManagedVec::new();
+        let mut seen_user_ids = AffectedUserIdVec::new();
+        self.foreach_fund_by_type(FundType::Waiting, SwapDirection::Forwards, |fund_item| {
+            if let Err(pos) = seen_user_ids.binary_search(&fund_item.user_id) {
+                seen_user_ids
+                    .try_insert(pos, fund_item.user_id)
+                    .unwrap_or_else(|_| sc_panic!("affected user capacity exceeded"));
+                user_ids.push(fund_item.user_id);
+            }
+        });
+        user_ids
+    }
+
+    /// Activates Waiting stake user by user, but no more than `max_per_user` for each of them.
+    /// Starts from `next_index` in `user_ids` and advances it, so that the operation can be continued.
+    fn swap_waiting_to_active_capped_per_user<I: Fn() -> bool>(
+        &self,
+        user_ids: &ManagedVec<usize>,
+        next_index: &mut usize,
+        remaining: &mut BigUint,
+        max_per_user: &BigUint,
+        interrupt: I,
+    ) {
+        while *next_index < user_ids.len() && *remaining > 0 && !interrupt() {
+            let mut user_max = core::cmp::min(&*remaining, max_per_user).clone();
+            let activated = self.split_convert_max_by_user(
+                Some(&mut user_max),
+                user_ids.get(*next_index),
+                FundType::Waiting,
+                SwapDirection::Forwards,
+                |_| Some(FundDescription::Active),
+                || false,
+            );
+            *remaining -= activated;
+            *next_index += 1;
+        }
+    }
+
     fn swap_eligible_deferred_to_withdraw<I: Fn() -> bool>(
         &self,
         user_id: usize,
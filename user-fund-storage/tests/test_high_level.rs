@@ -1,6 +1,8 @@
+#![allow(clippy::let_unit_value)]
+
 use multiversx_sc::types::BigUint;
 use multiversx_sc_scenario::DebugApi;
-use user_fund_storage::fund_module::FundModule;
+use user_fund_storage::fund_module::{FundModule, SwapDirection};
 use user_fund_storage::fund_transf_module::FundTransformationsModule;
 use user_fund_storage::types::FundType;
 
@@ -220,3 +222,112 @@ fn test_full_cycle_1() {
         module.count_fund_items_by_user_type(user_id, FundType::WithdrawOnly, |_| true)
     );
 }
+
+#[test]
+fn test_swap_active_to_deferred_payment_pro_rata() {
+    let _ = DebugApi::dummy();
+    let module = user_fund_storage::fund_transf_module::contract_obj::<DebugApi>();
+
+    module.create_waiting(1, 1000u32.into());
+    module.create_waiting(2, 2000u32.into());
+    module.create_waiting(3, 4000u32.into());
+    let mut amount = BigUint::from(7000u32);
+    let _ = module.swap_waiting_to_active(&mut amount, || false);
+    assert_eq!(amount, BigUint::zero());
+
+    // force out 1000 out of 7000, rounded down for each item
+    let total_swap = BigUint::from(1000u32);
+    let total_active = BigUint::from(7000u32);
+    let mut remaining = total_swap.clone();
+    let mut current_id = module.first_id_of_type(FundType::Active, SwapDirection::Forwards);
+    module.swap_active_to_deferred_payment_pro_rata(
+        &mut current_id,
+        &mut remaining,
+        &total_swap,
+        &total_active,
+        || false,
+    );
+    assert_eq!(current_id, 0);
+    assert_eq!(remaining, BigUint::from(2u32));
+
+    fund_module_check::check_consistency(&module, 3);
+    assert_eq!(
+        BigUint::from(142u32),
+        module.query_sum_funds_by_user_type(1, FundType::DeferredPayment, |_| true)
+    );
+    assert_eq!(
+        BigUint::from(285u32),
+        module.query_sum_funds_by_user_type(2, FundType::DeferredPayment, |_| true)
+    );
+    assert_eq!(
+        BigUint::from(571u32),
+        module.query_sum_funds_by_user_type(3, FundType::DeferredPayment, |_| true)
+    );
+    assert_eq!(
+        BigUint::from(6002u32),
+        module.query_sum_funds_by_type(FundType::Active, |_, _| true)
+    );
+    assert_eq!(
+        3,
+        module.count_fund_items_by_type(FundType::Active, |_| true)
+    );
+
+    // the rounding remainder goes the regular way
+    module.swap_active_to_deferred_payment(&mut remaining, || false);
+    assert_eq!(remaining, BigUint::zero());
+
+    fund_module_check::check_consistency(&module, 3);
+    assert_eq!(
+        BigUint::from(573u32),
+        module.query_sum_funds_by_user_type(3, FundType::DeferredPayment, |_| true)
+    );
+    assert_eq!(
+        BigUint::from(1000u32),
+        module.query_sum_funds_by_type(FundType::DeferredPayment, |_, _| true)
+    );
+}
+
+#[test]
+fn test_swap_waiting_to_active_capped_per_user() {
+    let _ = DebugApi::dummy();
+    let module = user_fund_storage::fund_transf_module::contract_obj::<DebugApi>();
+
+    module.create_waiting(2, 1000u32.into());
+    module.create_waiting(1, 3000u32.into());
+    module.create_waiting(2, 2000u32.into());
+
+    let user_ids = module.get_waiting_user_ids();
+    assert_eq!(user_ids.len(), 2);
+    assert_eq!(user_ids.get(0), 2);
+    assert_eq!(user_ids.get(1), 1);
+
+    let mut remaining = BigUint::from(5000u32);
+    let mut next_index = 0;
+    module.swap_waiting_to_active_capped_per_user(
+        &user_ids,
+        &mut next_index,
+        &mut remaining,
+        &BigUint::from(1500u32),
+        || false,
+    );
+    assert_eq!(next_index, 2);
+    assert_eq!(remaining, BigUint::from(2000u32));
+
+    fund_module_check::check_consistency(&module, 3);
+    assert_eq!(
+        BigUint::from(1500u32),
+        module.query_sum_funds_by_user_type(1, FundType::Active, |_| true)
+    );
+    assert_eq!(
+        BigUint::from(1500u32),
+        module.query_sum_funds_by_user_type(2, FundType::Active, |_| true)
+    );
+    assert_eq!(
+        BigUint::from(1500u32),
+        module.query_sum_funds_by_user_type(2, FundType::Waiting, |_| true)
+    );
+    assert_eq!(
+        1,
+        module.count_fund_items_by_user_type(2, FundType::Waiting, |_| true)
+    );
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::let_unit_value)]
+
 use multiversx_sc::types::BigUint;
 use multiversx_sc_scenario::DebugApi;
 use user_fund_storage::fund_module::*;